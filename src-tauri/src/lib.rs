@@ -270,12 +270,30 @@ pub fn run() {
             library::commands::library_start_scan,
             library::commands::library_cancel_operation,
             library::commands::library_get_assets,
+            library::commands::library_get_folder_contents,
             library::commands::library_get_asset,
             library::commands::library_get_dependencies,
             library::commands::library_get_dependents,
+            library::commands::library_get_parse_warnings,
+            library::commands::library_get_asset_history,
+            library::commands::library_get_changes_since,
             library::commands::library_get_type_counts,
+            library::commands::library_get_unused_assets,
+            library::commands::library_get_scene_sizes,
+            library::commands::library_get_upm_packages,
+            library::commands::library_get_package_usage,
+            library::commands::library_get_addressables,
+            library::commands::library_get_addressable_groups,
+            library::commands::library_get_broken_references,
+            library::commands::library_get_delete_impact,
+            library::commands::library_get_dependency_cycles,
+            library::commands::library_get_dependency_paths,
+            library::commands::library_get_script_usages,
+            library::commands::library_get_missing_scripts,
             library::commands::library_export_file,
             library::commands::library_export_bundle,
+            library::commands::library_import_bundle,
+            library::commands::library_export_dependency_graph,
             library::commands::library_reveal_in_explorer,
             library::commands::library_get_material_info,
             library::commands::library_get_model_info,
@@ -0,0 +1,1044 @@
+use crate::library::db::{
+    normalize_folder_path, parent_folder, AddressableAsset, Asset, DanglingReference, Database, Project,
+};
+use crate::library::deps::{
+    is_builtin_guid, meets_confidence, CONFIDENCE_HIGH, DEFAULT_PARSE_TIME_BUDGET, MONO_SCRIPT_FILE_ID,
+};
+use crate::library::error::{AppError, AppResult};
+use crate::library::graph::{Direction, GraphCache, GraphEdge};
+use crate::library::packages::{package_of_path, read_upm_packages};
+use crate::library::roots::{collect_entry_points, is_editor_only_path, read_addressable_labels, read_build_scenes};
+use crate::library::scanner::collect_package_guids;
+use crate::library::stream::read_text_chunks;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Asset types whose usage can't be judged from serialized references alone
+/// (scripts are referenced from code as much as from prefabs and scenes).
+const UNJUDGED_TYPES: &[&str] = &["script"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnusedAssetInfo {
+    pub id: String,
+    pub file_name: String,
+    pub relative_path: String,
+    pub asset_type: String,
+    pub size_bytes: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnusedFolderGroup {
+    pub folder: String,
+    pub asset_count: usize,
+    pub reclaimable_bytes: i64,
+    pub assets: Vec<UnusedAssetInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnusedAssetReport {
+    pub build_scene_roots: usize,
+    pub resources_roots: usize,
+    pub streaming_assets_roots: usize,
+    pub addressable_roots: usize,
+    /// Runtime scripts, rooted because they are compiled into every build
+    pub script_roots: usize,
+    /// Weakest edge confidence followed when walking from the roots
+    pub min_confidence: String,
+    pub reachable_count: usize,
+    /// Assets kept only by `medium`/`low` edges (string loads, data paths)
+    pub heuristic_only_count: usize,
+    pub unused_count: usize,
+    pub reclaimable_bytes: i64,
+    pub folders: Vec<UnusedFolderGroup>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrokenReferenceSource {
+    pub asset_id: String,
+    pub file_name: String,
+    pub relative_path: String,
+    pub asset_type: String,
+    pub relation_type: String,
+}
+
+/// Every asset still referencing one GUID that no longer resolves.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MissingGuidGroup {
+    pub guid: String,
+    pub reference_count: usize,
+    pub referencing_assets: Vec<BrokenReferenceSource>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrokenReferenceReport {
+    pub missing_guid_count: usize,
+    pub broken_reference_count: usize,
+    pub excluded_builtin: usize,
+    pub excluded_package: usize,
+    pub groups: Vec<MissingGuidGroup>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptUser {
+    pub asset_id: String,
+    pub file_name: String,
+    pub relative_path: String,
+    pub asset_type: String,
+    /// Components (or the ScriptableObject itself) in this asset backed by the script
+    pub component_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptUsageReport {
+    pub script_id: String,
+    pub script_path: String,
+    pub user_count: usize,
+    pub component_count: usize,
+    /// Number of using assets per asset type (prefab, scene, scriptable_object, ...)
+    pub users_by_type: BTreeMap<String, usize>,
+    pub users: Vec<ScriptUser>,
+}
+
+/// Components whose `m_Script` GUID has no script in the project or its packages.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MissingScriptReport {
+    pub missing_guid_count: usize,
+    pub affected_asset_count: usize,
+    pub excluded_package: usize,
+    pub groups: Vec<MissingGuidGroup>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImpactedAsset {
+    pub asset_id: String,
+    pub file_name: String,
+    pub relative_path: String,
+    pub asset_type: String,
+    pub size_bytes: i64,
+    /// Hops from the analyzed asset; 1 means it references the asset directly
+    pub depth: usize,
+    /// Relation of the edge through which this asset was first reached
+    pub relation_type: String,
+    pub is_entry_point: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImpactTypeGroup {
+    pub asset_type: String,
+    pub count: usize,
+    pub assets: Vec<ImpactedAsset>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteImpactReport {
+    pub asset_id: String,
+    pub relative_path: String,
+    pub max_depth: usize,
+    pub min_confidence: String,
+    pub direct_dependent_count: usize,
+    /// Dependents at any depth, including those beyond `max_depth`
+    pub total_dependent_count: usize,
+    /// Whether dependents exist beyond `max_depth` and are missing from `groups`
+    pub truncated: bool,
+    pub groups: Vec<ImpactTypeGroup>,
+    pub is_entry_point: bool,
+    /// Build entry points (scenes, Resources, Addressables, ...) that reach the asset
+    pub affected_entry_points: usize,
+    pub safe_to_delete: bool,
+    pub reasons: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CycleAsset {
+    pub asset_id: String,
+    pub file_name: String,
+    pub relative_path: String,
+    pub asset_type: String,
+}
+
+/// One `library_dependencies` edge with both ends' paths, for display.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyHop {
+    pub from_asset_id: String,
+    pub from_path: String,
+    pub to_asset_id: String,
+    pub to_path: String,
+    pub relation_type: String,
+}
+
+/// One strongly connected component: every member reaches every other member.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyCycle {
+    pub assets: Vec<CycleAsset>,
+    /// Every edge between members of the component
+    pub edges: Vec<DependencyHop>,
+    /// One shortest loop through the component, in traversal order
+    pub example_cycle: Vec<DependencyHop>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CycleReport {
+    pub component_count: usize,
+    pub asset_count: usize,
+    pub cycles: Vec<DependencyCycle>,
+}
+
+/// Shortest chains of edges explaining why `target` is pulled in by `root`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyPathReport {
+    pub root_id: String,
+    pub target_id: String,
+    /// Hops in each chain, `None` when the target isn't reachable from the root
+    pub hop_count: Option<usize>,
+    pub paths: Vec<Vec<DependencyHop>>,
+}
+
+/// One Addressables group: its entries and everything they pull in.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressableGroupReport {
+    pub group_name: String,
+    pub labels: Vec<String>,
+    /// Assets addressable through this group, folder entries expanded
+    pub asset_count: usize,
+    pub asset_bytes: i64,
+    /// Assets the group's entries depend on transitively, excluding the entries
+    pub dependency_count: usize,
+    pub dependency_bytes: i64,
+    /// Dependencies that aren't addressable themselves, so the build copies them
+    /// into this group's bundles
+    pub implicit_count: usize,
+    pub implicit_bytes: i64,
+    /// Implicit dependencies also pulled in by another group (duplicated content)
+    pub shared_implicit_count: usize,
+    pub shared_implicit_bytes: i64,
+    pub total_bytes: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressablesReport {
+    /// Labels defined in the Addressables settings, including unused ones
+    pub defined_labels: Vec<String>,
+    pub addressable_count: usize,
+    pub min_confidence: String,
+    pub groups: Vec<AddressableGroupReport>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypeBytes {
+    pub asset_type: String,
+    pub asset_count: usize,
+    pub bytes: i64,
+}
+
+/// Estimated content of one build scene, from source file sizes of its dependency closure.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SceneSize {
+    pub scene_id: String,
+    pub scene_path: String,
+    pub build_index: usize,
+    /// The scene and assets no other build scene reaches
+    pub unique_count: usize,
+    pub unique_bytes: i64,
+    pub unique_by_type: Vec<TypeBytes>,
+    /// Assets this scene has in common with at least one other build scene
+    pub shared_count: usize,
+    pub shared_bytes: i64,
+    pub shared_by_type: Vec<TypeBytes>,
+    pub total_bytes: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedSceneAsset {
+    pub asset_id: String,
+    pub relative_path: String,
+    pub asset_type: String,
+    pub size_bytes: i64,
+    pub scene_paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SceneSizeReport {
+    pub scenes: Vec<SceneSize>,
+    /// Enabled build settings entries with no indexed scene behind them
+    pub missing_scenes: Vec<String>,
+    pub unique_bytes: i64,
+    /// Assets reached by more than one scene, each counted once
+    pub shared_bytes: i64,
+    pub shared_by_type: Vec<TypeBytes>,
+    pub shared_assets: Vec<SharedSceneAsset>,
+    pub total_bytes: i64,
+    /// Weakest edge confidence followed when walking from each scene
+    pub min_confidence: String,
+}
+
+/// A project asset that references content from a package.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageUser {
+    pub asset_id: String,
+    pub relative_path: String,
+    pub asset_type: String,
+    /// Package assets this asset references directly
+    pub reference_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageUsage {
+    pub name: String,
+    pub version: Option<String>,
+    pub source: Option<String>,
+    pub direct: bool,
+    /// Listed in the manifest or lock file; false for package folders found on disk only
+    pub listed: bool,
+    pub indexed_asset_count: usize,
+    /// Distinct package assets referenced from project content
+    pub referenced_asset_count: usize,
+    pub users: Vec<PackageUser>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageUsageReport {
+    pub package_count: usize,
+    pub used_count: usize,
+    /// Packages ordered by how many project assets use them
+    pub packages: Vec<PackageUsage>,
+}
+
+pub struct UsageAnalyzer {
+    db: Arc<Database>,
+    graphs: Arc<GraphCache>,
+    package_cache_indexed: bool,
+    time_budget: Duration,
+}
+
+impl UsageAnalyzer {
+    pub fn new(db: Arc<Database>, graphs: Arc<GraphCache>) -> Self {
+        Self {
+            db,
+            graphs,
+            package_cache_indexed: false,
+            time_budget: DEFAULT_PARSE_TIME_BUDGET,
+        }
+    }
+
+    /// Whether scans index `Library/PackageCache`, so package GUIDs can come from the
+    /// index instead of reading every package `.meta` on disk.
+    pub fn with_package_cache_indexed(mut self, indexed: bool) -> Self {
+        self.package_cache_indexed = indexed;
+        self
+    }
+
+    /// How long re-reading a single file may take before its count is cut short.
+    pub fn with_time_budget(mut self, time_budget: Duration) -> Self {
+        self.time_budget = time_budget;
+        self
+    }
+
+    fn package_guids(&self, project: &Project) -> AppResult<HashSet<String>> {
+        if self.package_cache_indexed {
+            self.db.get_package_guids(&project.id)
+        } else {
+            Ok(collect_package_guids(Path::new(&project.root_path)))
+        }
+    }
+
+    /// Find assets under `Assets/` that no build entry point reaches through
+    /// `library_dependencies`, grouped by folder with the bytes they occupy. Only
+    /// edges of at least `min_confidence` are followed.
+    pub fn find_unused_assets(&self, project: &Project, min_confidence: &str) -> AppResult<UnusedAssetReport> {
+        let assets = self.db.get_all_assets(&project.id)?;
+        let entry_points = collect_entry_points(Path::new(&project.root_path), &assets);
+        let roots = &entry_points.asset_ids;
+
+        let graph = self.graphs.get(&self.db, &project.id)?;
+        let reachable = graph.reachable_from(roots, Direction::Dependencies, |e| {
+            meets_confidence(&e.confidence, min_confidence)
+        });
+        let parsed_reachable = graph.reachable_from(roots, Direction::Dependencies, |e| e.confidence == CONFIDENCE_HIGH);
+
+        let mut groups: BTreeMap<String, UnusedFolderGroup> = BTreeMap::new();
+        for asset in assets {
+            if reachable.contains(&asset.id)
+                || !normalize_folder_path(&asset.relative_path).starts_with("Assets/")
+                || is_editor_only_path(&asset.relative_path)
+                || UNJUDGED_TYPES.contains(&asset.asset_type.as_str())
+            {
+                continue;
+            }
+
+            let folder = parent_folder(&asset.relative_path);
+            let group = groups.entry(folder.clone()).or_insert_with(|| UnusedFolderGroup {
+                folder,
+                asset_count: 0,
+                reclaimable_bytes: 0,
+                assets: Vec::new(),
+            });
+            group.asset_count += 1;
+            group.reclaimable_bytes += asset.size_bytes;
+            group.assets.push(UnusedAssetInfo {
+                id: asset.id,
+                file_name: asset.file_name,
+                relative_path: asset.relative_path,
+                asset_type: asset.asset_type,
+                size_bytes: asset.size_bytes,
+            });
+        }
+
+        let mut folders: Vec<UnusedFolderGroup> = groups.into_values().collect();
+        folders.sort_by_key(|f| std::cmp::Reverse(f.reclaimable_bytes));
+
+        Ok(UnusedAssetReport {
+            build_scene_roots: entry_points.build_scenes,
+            resources_roots: entry_points.resources,
+            streaming_assets_roots: entry_points.streaming_assets,
+            addressable_roots: entry_points.addressables,
+            script_roots: entry_points.scripts,
+            min_confidence: min_confidence.to_string(),
+            reachable_count: reachable.len(),
+            heuristic_only_count: reachable.difference(&parsed_reachable).count(),
+            unused_count: folders.iter().map(|f| f.asset_count).sum(),
+            reclaimable_bytes: folders.iter().map(|f| f.reclaimable_bytes).sum(),
+            folders,
+        })
+    }
+
+    /// Group unresolved references by the missing GUID, ignoring Unity built-in
+    /// resources and GUIDs that belong to installed packages.
+    pub fn find_broken_references(&self, project: &Project) -> AppResult<BrokenReferenceReport> {
+        let dangling = self.db.get_dangling_references(&project.id)?;
+        let package_guids = self.package_guids(project)?;
+
+        let mut excluded_builtin = 0;
+        let mut excluded_package = 0;
+        let mut broken = Vec::new();
+
+        for reference in dangling {
+            if is_builtin_guid(&reference.to_guid) {
+                excluded_builtin += 1;
+                continue;
+            }
+            if package_guids.contains(&reference.to_guid) {
+                excluded_package += 1;
+                continue;
+            }
+
+            broken.push(reference);
+        }
+
+        let groups = group_by_guid(broken);
+
+        Ok(BrokenReferenceReport {
+            missing_guid_count: groups.len(),
+            broken_reference_count: groups.iter().map(|g| g.reference_count).sum(),
+            excluded_builtin,
+            excluded_package,
+            groups,
+        })
+    }
+
+    /// Walk dependents of `asset` transitively to see what a change or deletion would
+    /// affect. Dependents are listed up to `max_depth` hops, grouped by type; the
+    /// verdict always considers the full reverse closure against build entry points.
+    /// Only edges of at least `min_confidence` are followed.
+    pub fn find_delete_impact(
+        &self,
+        asset: &Asset,
+        max_depth: usize,
+        min_confidence: &str,
+    ) -> AppResult<DeleteImpactReport> {
+        let project = self
+            .db
+            .get_project(&asset.project_id)?
+            .ok_or_else(|| AppError::Custom("Project not found".to_string()))?;
+        let assets = self.db.get_all_assets(&project.id)?;
+        let entry_points = collect_entry_points(Path::new(&project.root_path), &assets);
+        let graph = self.graphs.get(&self.db, &project.id)?;
+
+        let by_id: HashMap<&str, &Asset> = assets.iter().map(|a| (a.id.as_str(), a)).collect();
+        let reached = graph.walk(&asset.id, Direction::Dependents, None, |e| {
+            meets_confidence(&e.confidence, min_confidence)
+        });
+
+        let mut groups: BTreeMap<String, ImpactTypeGroup> = BTreeMap::new();
+        let mut affected_entry_points = 0;
+        let mut truncated = false;
+
+        for hit in &reached {
+            let is_entry_point = entry_points.asset_ids.contains(&hit.asset_id);
+            if is_entry_point {
+                affected_entry_points += 1;
+            }
+            if hit.depth > max_depth {
+                truncated = true;
+                continue;
+            }
+
+            let dependent = match by_id.get(hit.asset_id.as_str()) {
+                Some(a) => a,
+                None => continue,
+            };
+            let group = groups
+                .entry(dependent.asset_type.clone())
+                .or_insert_with(|| ImpactTypeGroup {
+                    asset_type: dependent.asset_type.clone(),
+                    count: 0,
+                    assets: Vec::new(),
+                });
+            group.count += 1;
+            group.assets.push(ImpactedAsset {
+                asset_id: dependent.id.clone(),
+                file_name: dependent.file_name.clone(),
+                relative_path: dependent.relative_path.clone(),
+                asset_type: dependent.asset_type.clone(),
+                size_bytes: dependent.size_bytes,
+                depth: hit.depth,
+                relation_type: hit.via.relation_type.clone(),
+                is_entry_point,
+            });
+        }
+
+        let is_entry_point = entry_points.asset_ids.contains(&asset.id);
+        let direct_dependent_count = reached.iter().filter(|r| r.depth == 1).count();
+
+        let mut reasons = Vec::new();
+        if is_entry_point {
+            reasons.push(
+                "Asset is itself a build entry point (build scene, Resources, StreamingAssets, Addressables or runtime script)"
+                    .to_string(),
+            );
+        }
+        if affected_entry_points > 0 {
+            reasons.push(format!("Reached from {} build entry point(s)", affected_entry_points));
+        }
+        if UNJUDGED_TYPES.contains(&asset.asset_type.as_str()) {
+            reasons.push("Scripts can be referenced from code, which isn't tracked".to_string());
+        }
+        let safe_to_delete = reasons.is_empty();
+        if safe_to_delete && direct_dependent_count > 0 {
+            reasons.push(format!(
+                "{} dependent(s) outside the build will be left with missing references",
+                direct_dependent_count
+            ));
+        }
+
+        let mut groups: Vec<ImpactTypeGroup> = groups.into_values().collect();
+        groups.sort_by_key(|g| std::cmp::Reverse(g.count));
+        for group in &mut groups {
+            group
+                .assets
+                .sort_by(|a, b| a.depth.cmp(&b.depth).then_with(|| a.relative_path.cmp(&b.relative_path)));
+        }
+
+        Ok(DeleteImpactReport {
+            asset_id: asset.id.clone(),
+            relative_path: asset.relative_path.clone(),
+            max_depth,
+            min_confidence: min_confidence.to_string(),
+            direct_dependent_count,
+            total_dependent_count: reached.len(),
+            truncated,
+            groups,
+            is_entry_point,
+            affected_entry_points,
+            safe_to_delete,
+            reasons,
+        })
+    }
+
+    /// Find strongly connected components in the project's dependency graph, largest first.
+    pub fn find_dependency_cycles(&self, project: &Project) -> AppResult<CycleReport> {
+        let assets = self.db.get_all_assets(&project.id)?;
+        let graph = self.graphs.get(&self.db, &project.id)?;
+        let by_id: HashMap<&str, &Asset> = assets.iter().map(|a| (a.id.as_str(), a)).collect();
+
+        let path_of = |id: &str| by_id.get(id).map(|a| a.relative_path.clone()).unwrap_or_default();
+        let to_hop = |edge: &GraphEdge| DependencyHop {
+            from_asset_id: edge.from.clone(),
+            from_path: path_of(&edge.from),
+            to_asset_id: edge.to.clone(),
+            to_path: path_of(&edge.to),
+            relation_type: edge.relation_type.clone(),
+        };
+
+        let mut cycles = Vec::new();
+        for component in graph.cyclic_components() {
+            let members: HashSet<String> = component.iter().cloned().collect();
+
+            let mut cycle_assets: Vec<CycleAsset> = component
+                .iter()
+                .filter_map(|id| by_id.get(id.as_str()))
+                .map(|a| CycleAsset {
+                    asset_id: a.id.clone(),
+                    file_name: a.file_name.clone(),
+                    relative_path: a.relative_path.clone(),
+                    asset_type: a.asset_type.clone(),
+                })
+                .collect();
+            cycle_assets.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+
+            let mut edges: Vec<DependencyHop> = component
+                .iter()
+                .flat_map(|id| graph.dependencies(id))
+                .filter(|e| members.contains(&e.to))
+                .map(&to_hop)
+                .collect();
+            edges.sort_by(|a, b| a.from_path.cmp(&b.from_path).then_with(|| a.to_path.cmp(&b.to_path)));
+
+            let example_cycle = match cycle_assets.first() {
+                Some(first) => graph
+                    .cycle_through(&first.asset_id, &members)
+                    .iter()
+                    .map(&to_hop)
+                    .collect(),
+                None => Vec::new(),
+            };
+
+            cycles.push(DependencyCycle {
+                assets: cycle_assets,
+                edges,
+                example_cycle,
+            });
+        }
+
+        cycles.sort_by_key(|c| std::cmp::Reverse(c.assets.len()));
+
+        Ok(CycleReport {
+            component_count: cycles.len(),
+            asset_count: cycles.iter().map(|c| c.assets.len()).sum(),
+            cycles,
+        })
+    }
+
+    /// Find up to `max_paths` shortest dependency chains from `root` to `target`.
+    pub fn find_dependency_paths(
+        &self,
+        root: &Asset,
+        target: &Asset,
+        max_paths: usize,
+    ) -> AppResult<DependencyPathReport> {
+        let graph = self.graphs.get(&self.db, &root.project_id)?;
+        let chains = graph.shortest_paths(&root.id, &target.id, max_paths);
+
+        let mut paths_by_id: HashMap<String, String> = HashMap::new();
+        let mut path_of = |id: &str| -> AppResult<String> {
+            if let Some(path) = paths_by_id.get(id) {
+                return Ok(path.clone());
+            }
+            let path = self.db.get_asset(id)?.map(|a| a.relative_path).unwrap_or_default();
+            paths_by_id.insert(id.to_string(), path.clone());
+            Ok(path)
+        };
+
+        let mut paths = Vec::with_capacity(chains.len());
+        for chain in &chains {
+            let mut hops = Vec::with_capacity(chain.len());
+            for edge in chain {
+                hops.push(DependencyHop {
+                    from_asset_id: edge.from.clone(),
+                    from_path: path_of(&edge.from)?,
+                    to_asset_id: edge.to.clone(),
+                    to_path: path_of(&edge.to)?,
+                    relation_type: edge.relation_type.clone(),
+                });
+            }
+            paths.push(hops);
+        }
+
+        Ok(DependencyPathReport {
+            root_id: root.id.clone(),
+            target_id: target.id.clone(),
+            hop_count: paths.first().map(Vec::len),
+            paths,
+        })
+    }
+
+    /// List every asset whose components (or ScriptableObject) use `script`, with the
+    /// number of components per asset.
+    pub fn find_script_usages(&self, script: &Asset) -> AppResult<ScriptUsageReport> {
+        let users = self.db.get_script_users(&script.id)?;
+
+        let component_re = script.unity_guid.as_deref().map(|guid| {
+            Regex::new(&format!(
+                r"m_Script:\s*\{{\s*fileID:\s*{}\s*,\s*guid:\s*{}",
+                MONO_SCRIPT_FILE_ID, guid
+            ))
+            .unwrap()
+        });
+
+        let mut users_by_type: BTreeMap<String, usize> = BTreeMap::new();
+        let mut script_users = Vec::with_capacity(users.len());
+
+        for user in users {
+            // The edge itself proves at least one use, even if the file can't be re-read
+            let component_count = match &component_re {
+                Some(re) => {
+                    let mut count = 0;
+                    let _ = read_text_chunks(Path::new(&user.absolute_path), self.time_budget, |chunk| {
+                        count += re.find_iter(chunk).count();
+                        Ok(())
+                    });
+                    count.max(1)
+                }
+                None => 1,
+            };
+
+            *users_by_type.entry(user.asset_type.clone()).or_default() += 1;
+            script_users.push(ScriptUser {
+                asset_id: user.id,
+                file_name: user.file_name,
+                relative_path: user.relative_path,
+                asset_type: user.asset_type,
+                component_count,
+            });
+        }
+
+        Ok(ScriptUsageReport {
+            script_id: script.id.clone(),
+            script_path: script.relative_path.clone(),
+            user_count: script_users.len(),
+            component_count: script_users.iter().map(|u| u.component_count).sum(),
+            users_by_type,
+            users: script_users,
+        })
+    }
+
+    /// Group missing-script components by the GUID their `m_Script` still points at.
+    /// References to scripts shipped in installed packages are not reported.
+    pub fn find_missing_scripts(&self, project: &Project) -> AppResult<MissingScriptReport> {
+        let references = self.db.get_missing_script_references(&project.id)?;
+        let package_guids = self.package_guids(project)?;
+
+        let mut excluded_package = 0;
+        let mut missing = Vec::new();
+        for reference in references {
+            if package_guids.contains(&reference.to_guid) {
+                excluded_package += 1;
+                continue;
+            }
+            missing.push(reference);
+        }
+
+        let affected_asset_count = missing
+            .iter()
+            .map(|r| r.from_asset_id.as_str())
+            .collect::<HashSet<_>>()
+            .len();
+        let groups = group_by_guid(missing);
+
+        Ok(MissingScriptReport {
+            missing_guid_count: groups.len(),
+            affected_asset_count,
+            excluded_package,
+            groups,
+        })
+    }
+
+    /// Estimate the content of every enabled build scene by summing source file sizes
+    /// over its dependency closure, split into what only that scene uses and what it
+    /// shares with other scenes. Scripts are left out since source size says nothing
+    /// about compiled size. Only edges of at least `min_confidence` are followed.
+    pub fn find_scene_sizes(&self, project: &Project, min_confidence: &str) -> AppResult<SceneSizeReport> {
+        let assets = self.db.get_all_assets(&project.id)?;
+        let by_id: HashMap<&str, &Asset> = assets.iter().map(|a| (a.id.as_str(), a)).collect();
+        let by_guid: HashMap<&str, &Asset> = assets
+            .iter()
+            .filter_map(|a| a.unity_guid.as_deref().map(|g| (g, a)))
+            .collect();
+        let by_path: HashMap<String, &Asset> = assets
+            .iter()
+            .map(|a| (normalize_folder_path(&a.relative_path), a))
+            .collect();
+        let graph = self.graphs.get(&self.db, &project.id)?;
+
+        // Build indices count enabled scenes only
+        let mut scenes: Vec<(usize, &Asset, HashSet<String>)> = Vec::new();
+        let mut missing_scenes = Vec::new();
+        let build_scenes = read_build_scenes(Path::new(&project.root_path));
+        for (build_index, scene) in build_scenes.iter().filter(|s| s.enabled).enumerate() {
+            let asset = scene
+                .guid
+                .as_deref()
+                .and_then(|g| by_guid.get(g))
+                .or_else(|| by_path.get(&normalize_folder_path(&scene.path)));
+            match asset {
+                Some(asset) if !scenes.iter().any(|(_, s, _)| s.id == asset.id) => {
+                    let closure = graph.reachable_from([&asset.id], Direction::Dependencies, |e| {
+                        meets_confidence(&e.confidence, min_confidence)
+                    });
+                    scenes.push((build_index, asset, closure));
+                }
+                Some(_) => {}
+                None => missing_scenes.push(scene.path.clone()),
+            }
+        }
+
+        let mut scene_counts: HashMap<&str, Vec<&str>> = HashMap::new();
+        for (_, scene, closure) in &scenes {
+            for id in closure {
+                scene_counts.entry(id.as_str()).or_default().push(scene.relative_path.as_str());
+            }
+        }
+
+        let counted = |id: &str| by_id.get(id).filter(|a| !UNJUDGED_TYPES.contains(&a.asset_type.as_str())).copied();
+        let is_shared = |id: &str| scene_counts.get(id).map(|s| s.len() > 1).unwrap_or(false);
+
+        let mut scene_sizes = Vec::new();
+        for (build_index, scene, closure) in &scenes {
+            let (shared, unique): (Vec<&Asset>, Vec<&Asset>) =
+                closure.iter().filter_map(|id| counted(id)).partition(|a| is_shared(&a.id));
+            let unique_bytes: i64 = unique.iter().map(|a| a.size_bytes).sum();
+            let shared_bytes: i64 = shared.iter().map(|a| a.size_bytes).sum();
+
+            scene_sizes.push(SceneSize {
+                scene_id: scene.id.clone(),
+                scene_path: scene.relative_path.clone(),
+                build_index: *build_index,
+                unique_count: unique.len(),
+                unique_bytes,
+                unique_by_type: bytes_by_type(&unique),
+                shared_count: shared.len(),
+                shared_bytes,
+                shared_by_type: bytes_by_type(&shared),
+                total_bytes: unique_bytes + shared_bytes,
+            });
+        }
+
+        let shared: Vec<&Asset> = scene_counts
+            .iter()
+            .filter(|(_, scenes)| scenes.len() > 1)
+            .filter_map(|(id, _)| counted(id))
+            .collect();
+        let mut shared_assets: Vec<SharedSceneAsset> = shared
+            .iter()
+            .map(|a| SharedSceneAsset {
+                asset_id: a.id.clone(),
+                relative_path: a.relative_path.clone(),
+                asset_type: a.asset_type.clone(),
+                size_bytes: a.size_bytes,
+                scene_paths: scene_counts[a.id.as_str()].iter().map(|p| p.to_string()).collect(),
+            })
+            .collect();
+        shared_assets.sort_by(|a, b| b.size_bytes.cmp(&a.size_bytes).then_with(|| a.relative_path.cmp(&b.relative_path)));
+
+        let unique_bytes: i64 = scene_sizes.iter().map(|s| s.unique_bytes).sum();
+        let shared_bytes: i64 = shared.iter().map(|a| a.size_bytes).sum();
+
+        Ok(SceneSizeReport {
+            scenes: scene_sizes,
+            missing_scenes,
+            unique_bytes,
+            shared_bytes,
+            shared_by_type: bytes_by_type(&shared),
+            shared_assets,
+            total_bytes: unique_bytes + shared_bytes,
+            min_confidence: min_confidence.to_string(),
+        })
+    }
+
+    /// Which packages project content references directly, per package from the UPM
+    /// manifest and lock file plus any package folder that was indexed.
+    pub fn find_package_usage(&self, project: &Project) -> AppResult<PackageUsageReport> {
+        let assets = self.db.get_all_assets(&project.id)?;
+        let graph = self.graphs.get(&self.db, &project.id)?;
+
+        let mut packages: BTreeMap<String, PackageUsage> = read_upm_packages(Path::new(&project.root_path))
+            .into_iter()
+            .map(|p| {
+                (
+                    p.name.clone(),
+                    PackageUsage {
+                        name: p.name,
+                        version: Some(p.version).filter(|v| !v.is_empty()),
+                        source: p.source,
+                        direct: p.direct,
+                        listed: true,
+                        indexed_asset_count: 0,
+                        referenced_asset_count: 0,
+                        users: Vec::new(),
+                    },
+                )
+            })
+            .collect();
+
+        let package_of: HashMap<&str, String> = assets
+            .iter()
+            .filter_map(|a| package_of_path(&a.relative_path).map(|name| (a.id.as_str(), name)))
+            .collect();
+        for name in package_of.values() {
+            let usage = packages.entry(name.clone()).or_insert_with(|| PackageUsage {
+                name: name.clone(),
+                version: None,
+                source: None,
+                direct: false,
+                listed: false,
+                indexed_asset_count: 0,
+                referenced_asset_count: 0,
+                users: Vec::new(),
+            });
+            usage.indexed_asset_count += 1;
+        }
+
+        let mut referenced: HashMap<&str, HashSet<&str>> = HashMap::new();
+        for asset in assets.iter().filter(|a| !package_of.contains_key(a.id.as_str())) {
+            let mut per_package: BTreeMap<&str, usize> = BTreeMap::new();
+            for edge in graph.dependencies(&asset.id) {
+                if let Some(name) = package_of.get(edge.to.as_str()) {
+                    *per_package.entry(name.as_str()).or_default() += 1;
+                    referenced.entry(name.as_str()).or_default().insert(edge.to.as_str());
+                }
+            }
+
+            for (name, reference_count) in per_package {
+                if let Some(usage) = packages.get_mut(name) {
+                    usage.users.push(PackageUser {
+                        asset_id: asset.id.clone(),
+                        relative_path: asset.relative_path.clone(),
+                        asset_type: asset.asset_type.clone(),
+                        reference_count,
+                    });
+                }
+            }
+        }
+
+        let mut packages: Vec<PackageUsage> = packages.into_values().collect();
+        for usage in &mut packages {
+            usage.referenced_asset_count = referenced.get(usage.name.as_str()).map(|r| r.len()).unwrap_or(0);
+            usage.users.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+        }
+        packages.sort_by(|a, b| b.users.len().cmp(&a.users.len()).then_with(|| a.name.cmp(&b.name)));
+
+        Ok(PackageUsageReport {
+            package_count: packages.len(),
+            used_count: packages.iter().filter(|p| !p.users.is_empty()).count(),
+            packages,
+        })
+    }
+
+    /// Size each Addressables group by its own assets plus the transitive closure of
+    /// their dependencies, flagging implicit dependencies that several groups share.
+    /// Only edges of at least `min_confidence` are followed.
+    pub fn find_addressable_groups(&self, project: &Project, min_confidence: &str) -> AppResult<AddressablesReport> {
+        let addressables = self.db.get_addressables(&project.id, None, None)?;
+        let sizes: HashMap<String, i64> = self
+            .db
+            .get_all_assets(&project.id)?
+            .into_iter()
+            .map(|a| (a.id, a.size_bytes))
+            .collect();
+        let graph = self.graphs.get(&self.db, &project.id)?;
+        let addressable_ids: HashSet<&str> = addressables.iter().map(|a| a.asset_id.as_str()).collect();
+
+        let mut members: BTreeMap<&str, Vec<&AddressableAsset>> = BTreeMap::new();
+        for asset in &addressables {
+            members.entry(asset.group_name.as_str()).or_default().push(asset);
+        }
+
+        // Implicit dependencies of each group, to spot ones duplicated across groups
+        let mut closures: Vec<(&str, HashSet<String>)> = Vec::new();
+        for (group, assets) in &members {
+            let ids: Vec<String> = assets.iter().map(|a| a.asset_id.clone()).collect();
+            let mut closure = graph.reachable_from(&ids, Direction::Dependencies, |e| {
+                meets_confidence(&e.confidence, min_confidence)
+            });
+            for id in &ids {
+                closure.remove(id);
+            }
+            closures.push((group, closure));
+        }
+
+        let mut implicit_groups: HashMap<&str, usize> = HashMap::new();
+        for (_, closure) in &closures {
+            for id in closure.iter().filter(|id| !addressable_ids.contains(id.as_str())) {
+                *implicit_groups.entry(id.as_str()).or_default() += 1;
+            }
+        }
+
+        let size_of = |id: &str| sizes.get(id).copied().unwrap_or(0);
+        let mut groups = Vec::new();
+        for (group, closure) in &closures {
+            let assets = &members[group];
+            let labels: BTreeSet<&str> = assets.iter().flat_map(|a| a.labels.iter().map(String::as_str)).collect();
+            let asset_bytes: i64 = assets.iter().map(|a| a.size_bytes).sum();
+            let dependency_bytes: i64 = closure.iter().map(|id| size_of(id)).sum();
+
+            let implicit: Vec<&String> = closure.iter().filter(|id| !addressable_ids.contains(id.as_str())).collect();
+            let shared: Vec<&&String> = implicit
+                .iter()
+                .filter(|id| implicit_groups.get(id.as_str()).copied().unwrap_or(0) > 1)
+                .collect();
+
+            groups.push(AddressableGroupReport {
+                group_name: group.to_string(),
+                labels: labels.into_iter().map(str::to_string).collect(),
+                asset_count: assets.len(),
+                asset_bytes,
+                dependency_count: closure.len(),
+                dependency_bytes,
+                implicit_count: implicit.len(),
+                implicit_bytes: implicit.iter().map(|id| size_of(id)).sum(),
+                shared_implicit_count: shared.len(),
+                shared_implicit_bytes: shared.iter().map(|id| size_of(id)).sum(),
+                total_bytes: asset_bytes + dependency_bytes,
+            });
+        }
+
+        Ok(AddressablesReport {
+            defined_labels: read_addressable_labels(Path::new(&project.root_path)),
+            addressable_count: addressables.len(),
+            min_confidence: min_confidence.to_string(),
+            groups,
+        })
+    }
+}
+
+/// Count and size assets per type, largest first.
+fn bytes_by_type(assets: &[&Asset]) -> Vec<TypeBytes> {
+    let mut by_type: HashMap<&str, TypeBytes> = HashMap::new();
+    for asset in assets {
+        let entry = by_type.entry(asset.asset_type.as_str()).or_insert_with(|| TypeBytes {
+            asset_type: asset.asset_type.clone(),
+            asset_count: 0,
+            bytes: 0,
+        });
+        entry.asset_count += 1;
+        entry.bytes += asset.size_bytes;
+    }
+
+    let mut types: Vec<TypeBytes> = by_type.into_values().collect();
+    types.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.asset_type.cmp(&b.asset_type)));
+    types
+}
+
+/// Group unresolved references by GUID, most-referenced first.
+fn group_by_guid(references: Vec<DanglingReference>) -> Vec<MissingGuidGroup> {
+    let mut groups: HashMap<String, MissingGuidGroup> = HashMap::new();
+
+    for reference in references {
+        let group = groups
+            .entry(reference.to_guid.clone())
+            .or_insert_with(|| MissingGuidGroup {
+                guid: reference.to_guid.clone(),
+                reference_count: 0,
+                referencing_assets: Vec::new(),
+            });
+        group.reference_count += 1;
+        group.referencing_assets.push(BrokenReferenceSource {
+            asset_id: reference.from_asset_id,
+            file_name: reference.from_file_name,
+            relative_path: reference.from_relative_path,
+            asset_type: reference.from_asset_type,
+            relation_type: reference.relation_type,
+        });
+    }
+
+    let mut groups: Vec<MissingGuidGroup> = groups.into_values().collect();
+    groups.sort_by(|a, b| {
+        b.reference_count
+            .cmp(&a.reference_count)
+            .then_with(|| a.guid.cmp(&b.guid))
+    });
+    groups
+}
@@ -0,0 +1,268 @@
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Metadata larger than this is treated as a corrupt header rather than read.
+const MAX_METADATA_SIZE: u64 = 256 * 1024 * 1024;
+
+/// GUID of a file referenced from a binary serialized asset's externals table.
+#[derive(Debug, Clone)]
+pub struct ExternalReference {
+    pub guid: String,
+}
+
+/// What can be recovered from a binary serialized file without its type information.
+#[derive(Debug, Clone)]
+pub struct SerializedFileInfo {
+    pub format_version: u32,
+    pub unity_version: String,
+    pub externals: Vec<ExternalReference>,
+}
+
+/// Whether the file starts with the `%YAML` header that text serialization writes.
+pub fn has_yaml_header(path: &Path) -> bool {
+    let mut magic = [0u8; 5];
+    File::open(path)
+        .and_then(|mut f| f.read_exact(&mut magic))
+        .map(|_| &magic == b"%YAML")
+        .unwrap_or(false)
+}
+
+/// Read the header and metadata of a Unity binary SerializedFile (Force Binary or Mixed
+/// serialization) and return its externals table. Only the metadata block is read, so
+/// large scenes don't get loaded whole. `None` if the file isn't a SerializedFile this
+/// parser understands.
+pub fn read_serialized_file_info(path: &Path) -> Option<SerializedFileInfo> {
+    let mut file = File::open(path).ok()?;
+    let actual_size = file.metadata().ok()?.len();
+
+    let mut header = [0u8; 48];
+    let header_len = file.read(&mut header).ok()?;
+    let mut reader = ByteReader::new(&header[..header_len], true);
+
+    let mut metadata_size = reader.u32()? as u64;
+    let mut file_size = reader.u32()? as u64;
+    let version = reader.u32()?;
+    let _data_offset = reader.u32()?;
+
+    // Format 5 is Unity 2.x; anything far past the current format is not a SerializedFile
+    if !(5..=50).contains(&version) {
+        return None;
+    }
+
+    let (metadata_start, big_endian) = if version >= 9 {
+        let big_endian = reader.u8()? != 0;
+        reader.skip(3)?;
+        if version >= 22 {
+            metadata_size = reader.u32()? as u64;
+            file_size = reader.u64()?;
+            let _data_offset = reader.u64()?;
+            reader.skip(8)?;
+        }
+        (reader.position() as u64, big_endian)
+    } else {
+        // Old formats keep the metadata, led by the endianness byte, at the end of the file
+        (file_size.checked_sub(metadata_size)?, false)
+    };
+
+    if file_size != actual_size || metadata_size == 0 || metadata_size > MAX_METADATA_SIZE {
+        return None;
+    }
+    if metadata_start.checked_add(metadata_size)? > actual_size {
+        return None;
+    }
+
+    let mut metadata = vec![0u8; metadata_size as usize];
+    file.seek(SeekFrom::Start(metadata_start)).ok()?;
+    file.read_exact(&mut metadata).ok()?;
+
+    let mut reader = ByteReader::new(&metadata, big_endian);
+    if version < 9 {
+        reader.big_endian = reader.u8()? != 0;
+    }
+
+    parse_metadata(&mut reader, version)
+}
+
+fn parse_metadata(reader: &mut ByteReader, version: u32) -> Option<SerializedFileInfo> {
+    let unity_version = if version >= 7 { reader.cstring()? } else { String::new() };
+    if version >= 8 {
+        let _target_platform = reader.i32()?;
+    }
+    let enable_type_tree = if version >= 13 { reader.u8()? != 0 } else { true };
+
+    let type_count = reader.count()?;
+    for _ in 0..type_count {
+        skip_serialized_type(reader, version, enable_type_tree)?;
+    }
+
+    let big_id_enabled = (7..14).contains(&version) && reader.i32()? != 0;
+
+    let object_count = reader.count()?;
+    for _ in 0..object_count {
+        if big_id_enabled {
+            reader.skip(8)?;
+        } else if version < 14 {
+            reader.skip(4)?;
+        } else {
+            reader.align(4);
+            reader.skip(8)?;
+        }
+        reader.skip(if version >= 22 { 8 } else { 4 })?; // byte start
+        reader.skip(4)?; // byte size
+        reader.skip(4)?; // type id
+        if version < 16 {
+            reader.skip(2)?; // class id
+        }
+        if version < 11 {
+            reader.skip(2)?; // is destroyed
+        }
+        if (11..17).contains(&version) {
+            reader.skip(2)?; // script type index
+        }
+        if version == 15 || version == 16 {
+            reader.skip(1)?; // stripped
+        }
+    }
+
+    if version >= 11 {
+        let script_count = reader.count()?;
+        for _ in 0..script_count {
+            reader.skip(4)?; // local serialized file index
+            if version < 14 {
+                reader.skip(4)?;
+            } else {
+                reader.align(4);
+                reader.skip(8)?;
+            }
+        }
+    }
+
+    let external_count = reader.count()?;
+    let mut externals = Vec::with_capacity(external_count);
+    for _ in 0..external_count {
+        if version >= 6 {
+            let _temp_empty = reader.cstring()?;
+        }
+        let guid = format_guid(reader.bytes(16)?);
+        let _type = reader.i32()?;
+        let _path = reader.cstring()?;
+        externals.push(ExternalReference { guid });
+    }
+
+    Some(SerializedFileInfo {
+        format_version: version,
+        unity_version,
+        externals,
+    })
+}
+
+fn skip_serialized_type(reader: &mut ByteReader, version: u32, enable_type_tree: bool) -> Option<()> {
+    let class_id = reader.i32()?;
+    if version >= 16 {
+        reader.skip(1)?; // stripped
+    }
+    if version >= 17 {
+        reader.skip(2)?; // script type index
+    }
+    if version >= 13 {
+        // MonoBehaviour types carry a script hash ahead of the type hash
+        if (version < 16 && class_id < 0) || (version >= 16 && class_id == 114) {
+            reader.skip(16)?;
+        }
+        reader.skip(16)?;
+    }
+
+    if enable_type_tree {
+        // The pre-Unity 5 recursive type tree layout isn't supported
+        if version < 12 && version != 10 {
+            return None;
+        }
+        let node_count = reader.count()?;
+        let string_buffer_size = reader.count()?;
+        let node_size = if version >= 19 { 32 } else { 24 };
+        reader.skip(node_count.checked_mul(node_size)?)?;
+        reader.skip(string_buffer_size)?;
+        if version >= 21 {
+            let dependency_count = reader.count()?;
+            reader.skip(dependency_count.checked_mul(4)?)?;
+        }
+    }
+
+    Some(())
+}
+
+/// Unity prints each GUID byte low nibble first.
+fn format_guid(bytes: &[u8]) -> String {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+    let mut guid = String::with_capacity(32);
+    for byte in bytes {
+        guid.push(HEX[(byte & 0x0f) as usize] as char);
+        guid.push(HEX[(byte >> 4) as usize] as char);
+    }
+    guid
+}
+
+/// Bounds-checked cursor over a byte slice; every read returns `None` past the end.
+struct ByteReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    big_endian: bool,
+}
+
+impl<'a> ByteReader<'a> {
+    fn new(data: &'a [u8], big_endian: bool) -> Self {
+        Self { data, pos: 0, big_endian }
+    }
+
+    fn position(&self) -> usize {
+        self.pos
+    }
+
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let end = self.pos.checked_add(len)?;
+        let slice = self.data.get(self.pos..end)?;
+        self.pos = end;
+        Some(slice)
+    }
+
+    fn skip(&mut self, len: usize) -> Option<()> {
+        self.bytes(len).map(|_| ())
+    }
+
+    fn align(&mut self, to: usize) {
+        self.pos = self.pos.div_ceil(to) * to;
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.bytes(1).map(|b| b[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let b: [u8; 4] = self.bytes(4)?.try_into().ok()?;
+        Some(if self.big_endian { u32::from_be_bytes(b) } else { u32::from_le_bytes(b) })
+    }
+
+    fn i32(&mut self) -> Option<i32> {
+        self.u32().map(|v| v as i32)
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        let b: [u8; 8] = self.bytes(8)?.try_into().ok()?;
+        Some(if self.big_endian { u64::from_be_bytes(b) } else { u64::from_le_bytes(b) })
+    }
+
+    /// A non-negative element count that can't exceed the remaining bytes.
+    fn count(&mut self) -> Option<usize> {
+        let count = usize::try_from(self.i32()?).ok()?;
+        (count <= self.data.len() - self.pos.min(self.data.len())).then_some(count)
+    }
+
+    fn cstring(&mut self) -> Option<String> {
+        let rest = self.data.get(self.pos..)?;
+        let len = rest.iter().position(|&b| b == 0)?;
+        let value = String::from_utf8_lossy(&rest[..len]).to_string();
+        self.pos += len + 1;
+        Some(value)
+    }
+}
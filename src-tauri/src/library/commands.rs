@@ -1,13 +1,28 @@
-use crate::library::db::{Asset, Dependency, Project, TypeCount};
-use crate::library::deps::DependencyResolver;
+use crate::library::analysis::{
+    AddressablesReport, BrokenReferenceReport, CycleReport, DeleteImpactReport, DependencyPathReport,
+    MissingScriptReport, PackageUsageReport, SceneSizeReport, ScriptUsageReport, UnusedAssetReport, UsageAnalyzer,
+};
+use crate::library::db::{
+    normalize_folder_path, AddressableAsset, Asset, AssetFilter, AssetHistoryEntry, Dependency, FolderContents,
+    ParseWarning, Project, TypeCount,
+};
+use crate::library::deps::{meets_confidence, DependencyResolver, CONFIDENCE_HIGH, CONFIDENCE_LOW};
 use crate::library::error::AppError;
-use crate::library::export::{ExportResult, Exporter};
+use crate::library::export::{ConflictPolicy, ExportOptions, ExportResult, Exporter};
+use crate::library::graph_export::{GraphExportResult, GraphExporter, GraphScope};
+use crate::library::import::{ImportResult, Importer};
 use crate::library::indexer::Indexer;
+use crate::library::packages::{asset_scope, read_upm_packages, UpmPackage, SCOPE_PROJECT};
 use crate::library::previews::{parse_material_file, parse_model_info, MaterialInfo, ModelInfo, PreviewGenerator};
-use crate::library::scanner::{count_scannable_files, scan_files_batch, ScanStats};
+use crate::library::roots::resolve_addressable_assets;
+use crate::library::scanner::{
+    count_scannable_files, scan_files_batch, scan_package_cache, scan_subtree, ScanOptions, ScanStats,
+};
 use crate::library::state::LibraryState;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::path::Path;
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use tauri::{Emitter, State};
 
@@ -152,6 +167,7 @@ pub async fn library_start_scan(
     }
 
     let db = Arc::clone(&state.db);
+    let graphs = Arc::clone(&state.graphs);
     let settings = state.settings.read().clone();
     let cancel_flag = Arc::clone(&state.cancel_flag);
     let scan_running = Arc::clone(&state.scan_running);
@@ -168,6 +184,9 @@ pub async fn library_start_scan(
     let root_path = project.root_path.clone();
     let project_id_clone = project_id.clone();
     let ignore_patterns = settings.ignore_patterns.clone();
+    let shader_include_roots = settings.shader_include_roots.clone();
+    let parse_time_budget = std::time::Duration::from_secs(settings.parse_time_budget_secs);
+    let index_package_cache = settings.index_package_cache;
 
     // Emit initial event BEFORE spawn_blocking to ensure events work
     tracing::info!("Emitting initial scan event before spawn...");
@@ -196,7 +215,6 @@ pub async fn library_start_scan(
         let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
             tracing::info!("Scan task started for: {}", root_path);
             let db_clone = Arc::clone(&db);
-            let indexer = Indexer::new(Arc::clone(&db));
             let mut last_refresh = std::time::Instant::now();
 
             let existing_assets = match db.get_existing_asset_info(&project_id_clone) {
@@ -215,6 +233,8 @@ pub async fn library_start_scan(
                 }
             };
 
+            let indexer = Indexer::new(Arc::clone(&db)).with_history(existing_assets.is_some());
+
             // Phase 0: Count files
             tracing::info!("Emitting counting phase event...");
             match app_handle.emit(
@@ -289,13 +309,17 @@ pub async fn library_start_scan(
 
         let cancel_flag_scan = Arc::clone(&cancel_flag);
         let mut final_stats = ScanStats::default();
+        let mut seen_paths = HashSet::new();
         let total = scan_files_batch(
             Path::new(&root_path),
-            &project_id_clone,
-            &ignore_patterns,
-            25,
-            cancel_flag_scan,
-            existing_assets.as_ref(),
+            ScanOptions {
+                project_id: &project_id_clone,
+                ignore_patterns: &ignore_patterns,
+                batch_size: 25,
+                cancel_flag: cancel_flag_scan,
+                existing_assets: existing_assets.as_ref(),
+            },
+            &mut seen_paths,
             |batch, count, current_path| {
                 if let Err(e) = indexer.upsert_batch(&batch) {
                     tracing::error!("Failed to index batch: {}", e);
@@ -332,6 +356,70 @@ pub async fn library_start_scan(
             );
         }
 
+        // Package rows are only reconciled after a full package pass; with package
+        // indexing turned off there is nothing to keep them for
+        let mut packages_complete = !index_package_cache;
+
+        // Package contents are indexed into their own scope after the project itself
+        if index_package_cache && total.is_ok() && !cancel_flag.load(std::sync::atomic::Ordering::SeqCst) {
+            let _ = app_handle.emit(
+                "library-scan-progress",
+                ScanProgress {
+                    scanned: 0,
+                    total: None,
+                    current_path: "".to_string(),
+                    phase: "packages".to_string(),
+                    skipped: None,
+                    changed: None,
+                },
+            );
+
+            // Cached package files are not part of the project's own history
+            let package_indexer = Indexer::new(Arc::clone(&db));
+            let packages = scan_package_cache(
+                Path::new(&root_path),
+                ScanOptions {
+                    project_id: &project_id_clone,
+                    ignore_patterns: &ignore_patterns,
+                    batch_size: 25,
+                    cancel_flag: Arc::clone(&cancel_flag),
+                    existing_assets: existing_assets.as_ref(),
+                },
+                &mut seen_paths,
+                |batch, count, current_path| {
+                    if let Err(e) = package_indexer.upsert_batch(&batch) {
+                        tracing::error!("Failed to index package batch: {}", e);
+                    }
+
+                    let _ = app_handle.emit(
+                        "library-scan-progress",
+                        ScanProgress {
+                            scanned: count,
+                            total: None,
+                            current_path: current_path.to_string(),
+                            phase: "packages".to_string(),
+                            skipped: None,
+                            changed: None,
+                        },
+                    );
+
+                    !cancel_flag.load(std::sync::atomic::Ordering::SeqCst)
+                },
+            );
+
+            match packages {
+                Ok((count, stats)) => {
+                    tracing::info!(
+                        "Package scan complete: {} files, {} new/changed",
+                        count,
+                        stats.new_or_changed
+                    );
+                    packages_complete = !cancel_flag.load(std::sync::atomic::Ordering::SeqCst);
+                }
+                Err(e) => tracing::error!("Failed to scan package cache: {}", e),
+            }
+        }
+
         if cancel_flag.load(std::sync::atomic::Ordering::SeqCst) {
             let _ = app_handle.emit(
                 "library-scan-progress",
@@ -348,8 +436,32 @@ pub async fn library_start_scan(
             return;
         }
 
+        // Files that were indexed before but not found this time were moved or deleted
+        if let (Ok(_), Some(existing)) = (&total, &existing_assets) {
+            match indexer.reconcile_missing(&project_id_clone, existing, &seen_paths, packages_complete) {
+                Ok(0) => {}
+                Ok(removed) => {
+                    tracing::info!("Removed {} moved or deleted assets", removed);
+                    // Moves re-point incoming edges, so patching the cached graph isn't enough
+                    graphs.invalidate(&project_id_clone);
+                }
+                Err(e) => tracing::error!("Failed to reconcile missing assets: {}", e),
+            }
+        }
+
         let file_count = total.map(|(count, _)| count).unwrap_or(0) as i64;
 
+        // Group membership follows GUIDs, so it is rebuilt once the asset rows are current
+        match db_clone.get_all_assets(&project_id_clone) {
+            Ok(assets) => {
+                let addressables = resolve_addressable_assets(Path::new(&root_path), &assets);
+                if let Err(e) = db_clone.replace_addressables(&project_id_clone, &addressables) {
+                    tracing::error!("Failed to store Addressables groups: {}", e);
+                }
+            }
+            Err(e) => tracing::error!("Failed to load assets for Addressables groups: {}", e),
+        }
+
         let _ = app_handle.emit("library-assets-updated", file_count);
 
         // Phase 2: Resolve dependencies
@@ -365,27 +477,42 @@ pub async fn library_start_scan(
             },
         );
 
-        let dep_resolver = DependencyResolver::new(Arc::clone(&db_clone));
+        let dep_resolver = DependencyResolver::new(Arc::clone(&db_clone), Arc::clone(&graphs))
+            .with_include_roots(shader_include_roots)
+            .with_time_budget(parse_time_budget);
         let cancel_flag_deps = Arc::clone(&cancel_flag);
         let app_handle_deps = app_handle.clone();
         let stats_for_deps = final_stats;
-        if let Err(e) = dep_resolver.resolve_all_for_project_with_progress(
-            &project_id_clone,
-            cancel_flag_deps,
-            |processed, total| {
-                let _ = app_handle_deps.emit(
-                    "library-scan-progress",
-                    ScanProgress {
-                        scanned: processed,
-                        total: Some(total),
-                        current_path: "".to_string(),
-                        phase: "dependencies".to_string(),
-                        skipped: Some(stats_for_deps.unchanged_skipped),
-                        changed: Some(stats_for_deps.new_or_changed),
-                    },
-                );
-            },
-        ) {
+        let report_deps_progress = |processed: usize, total: usize| {
+            let _ = app_handle_deps.emit(
+                "library-scan-progress",
+                ScanProgress {
+                    scanned: processed,
+                    total: Some(total),
+                    current_path: "".to_string(),
+                    phase: "dependencies".to_string(),
+                    skipped: Some(stats_for_deps.unchanged_skipped),
+                    changed: Some(stats_for_deps.new_or_changed),
+                },
+            );
+        };
+
+        // On re-scans only assets whose dependencies are stale get re-parsed
+        let deps_result = if existing_assets.is_some() {
+            dep_resolver.resolve_changed_with_progress(
+                &project_id_clone,
+                cancel_flag_deps,
+                report_deps_progress,
+            )
+        } else {
+            dep_resolver.resolve_all_for_project_with_progress(
+                &project_id_clone,
+                cancel_flag_deps,
+                report_deps_progress,
+            )
+        };
+
+        if let Err(e) = deps_result {
             tracing::error!("Failed to resolve dependencies: {}", e);
         }
 
@@ -456,23 +583,27 @@ pub async fn library_cancel_operation(state: State<'_, LibraryState>) -> Result<
 #[tauri::command]
 pub async fn library_get_assets(
     project_id: String,
-    search_query: Option<String>,
-    asset_types: Option<Vec<String>>,
+    filter: Option<AssetFilter>,
     page: i64,
     page_size: i64,
     state: State<'_, LibraryState>,
 ) -> Result<AssetsResponse, AppError> {
-    let (assets, total) = state.db.get_assets(
-        &project_id,
-        search_query.as_deref(),
-        asset_types.as_deref(),
-        page,
-        page_size,
-    )?;
+    let (assets, total) = state.db.get_assets(&project_id, &filter.unwrap_or_default(), page, page_size)?;
 
     Ok(AssetsResponse { assets, total })
 }
 
+#[tauri::command]
+pub async fn library_get_folder_contents(
+    project_id: String,
+    folder_path: Option<String>,
+    state: State<'_, LibraryState>,
+) -> Result<FolderContents, AppError> {
+    state
+        .db
+        .get_folder_contents(&project_id, folder_path.as_deref().unwrap_or(""))
+}
+
 #[tauri::command]
 pub async fn library_get_asset(id: String, state: State<'_, LibraryState>) -> Result<Asset, AppError> {
     state
@@ -484,17 +615,53 @@ pub async fn library_get_asset(id: String, state: State<'_, LibraryState>) -> Re
 #[tauri::command]
 pub async fn library_get_dependencies(
     asset_id: String,
+    min_confidence: Option<String>,
     state: State<'_, LibraryState>,
 ) -> Result<Vec<Dependency>, AppError> {
-    state.db.get_dependencies(&asset_id)
+    let mut deps = state.db.get_dependencies(&asset_id)?;
+    if let Some(min) = min_confidence {
+        deps.retain(|d| meets_confidence(&d.confidence, &min));
+    }
+    Ok(deps)
 }
 
 #[tauri::command]
 pub async fn library_get_dependents(
     asset_id: String,
+    min_confidence: Option<String>,
     state: State<'_, LibraryState>,
 ) -> Result<Vec<Dependency>, AppError> {
-    state.db.get_dependents(&asset_id)
+    let mut deps = state.db.get_dependents(&asset_id)?;
+    if let Some(min) = min_confidence {
+        deps.retain(|d| meets_confidence(&d.confidence, &min));
+    }
+    Ok(deps)
+}
+
+#[tauri::command]
+pub async fn library_get_parse_warnings(
+    project_id: String,
+    kind: Option<String>,
+    state: State<'_, LibraryState>,
+) -> Result<Vec<ParseWarning>, AppError> {
+    state.db.get_parse_warnings(&project_id, kind.as_deref())
+}
+
+#[tauri::command]
+pub async fn library_get_asset_history(
+    asset_id: String,
+    state: State<'_, LibraryState>,
+) -> Result<Vec<AssetHistoryEntry>, AppError> {
+    state.db.get_asset_history(&asset_id)
+}
+
+#[tauri::command]
+pub async fn library_get_changes_since(
+    project_id: String,
+    since: i64,
+    state: State<'_, LibraryState>,
+) -> Result<Vec<AssetHistoryEntry>, AppError> {
+    state.db.get_changes_since(&project_id, since)
 }
 
 #[tauri::command]
@@ -505,10 +672,217 @@ pub async fn library_get_type_counts(
     state.db.get_type_counts(&project_id)
 }
 
+#[tauri::command]
+pub async fn library_get_unused_assets(
+    project_id: String,
+    min_confidence: Option<String>,
+    state: State<'_, LibraryState>,
+) -> Result<UnusedAssetReport, AppError> {
+    let project = state
+        .db
+        .get_project(&project_id)?
+        .ok_or_else(|| AppError::Custom("Project not found".to_string()))?;
+
+    let analyzer = UsageAnalyzer::new(Arc::clone(&state.db), Arc::clone(&state.graphs));
+    analyzer.find_unused_assets(&project, min_confidence.as_deref().unwrap_or(CONFIDENCE_LOW))
+}
+
+#[tauri::command]
+pub async fn library_get_upm_packages(
+    project_id: String,
+    state: State<'_, LibraryState>,
+) -> Result<Vec<UpmPackage>, AppError> {
+    let project = state
+        .db
+        .get_project(&project_id)?
+        .ok_or_else(|| AppError::Custom("Project not found".to_string()))?;
+
+    Ok(read_upm_packages(Path::new(&project.root_path)))
+}
+
+#[tauri::command]
+pub async fn library_get_package_usage(
+    project_id: String,
+    state: State<'_, LibraryState>,
+) -> Result<PackageUsageReport, AppError> {
+    let project = state
+        .db
+        .get_project(&project_id)?
+        .ok_or_else(|| AppError::Custom("Project not found".to_string()))?;
+
+    let analyzer = UsageAnalyzer::new(Arc::clone(&state.db), Arc::clone(&state.graphs));
+    analyzer.find_package_usage(&project)
+}
+
+#[tauri::command]
+pub async fn library_get_scene_sizes(
+    project_id: String,
+    min_confidence: Option<String>,
+    state: State<'_, LibraryState>,
+) -> Result<SceneSizeReport, AppError> {
+    let project = state
+        .db
+        .get_project(&project_id)?
+        .ok_or_else(|| AppError::Custom("Project not found".to_string()))?;
+
+    let analyzer = UsageAnalyzer::new(Arc::clone(&state.db), Arc::clone(&state.graphs));
+    analyzer.find_scene_sizes(&project, min_confidence.as_deref().unwrap_or(CONFIDENCE_HIGH))
+}
+
+#[tauri::command]
+pub async fn library_get_addressables(
+    project_id: String,
+    group: Option<String>,
+    label: Option<String>,
+    state: State<'_, LibraryState>,
+) -> Result<Vec<AddressableAsset>, AppError> {
+    state.db.get_addressables(&project_id, group.as_deref(), label.as_deref())
+}
+
+#[tauri::command]
+pub async fn library_get_addressable_groups(
+    project_id: String,
+    min_confidence: Option<String>,
+    state: State<'_, LibraryState>,
+) -> Result<AddressablesReport, AppError> {
+    let project = state
+        .db
+        .get_project(&project_id)?
+        .ok_or_else(|| AppError::Custom("Project not found".to_string()))?;
+
+    let analyzer = UsageAnalyzer::new(Arc::clone(&state.db), Arc::clone(&state.graphs));
+    analyzer.find_addressable_groups(&project, min_confidence.as_deref().unwrap_or(CONFIDENCE_HIGH))
+}
+
+#[tauri::command]
+pub async fn library_get_broken_references(
+    project_id: String,
+    state: State<'_, LibraryState>,
+) -> Result<BrokenReferenceReport, AppError> {
+    let project = state
+        .db
+        .get_project(&project_id)?
+        .ok_or_else(|| AppError::Custom("Project not found".to_string()))?;
+
+    let analyzer = UsageAnalyzer::new(Arc::clone(&state.db), Arc::clone(&state.graphs))
+        .with_package_cache_indexed(state.settings.read().index_package_cache);
+    analyzer.find_broken_references(&project)
+}
+
+#[tauri::command]
+pub async fn library_get_dependency_cycles(
+    project_id: String,
+    state: State<'_, LibraryState>,
+) -> Result<CycleReport, AppError> {
+    let project = state
+        .db
+        .get_project(&project_id)?
+        .ok_or_else(|| AppError::Custom("Project not found".to_string()))?;
+
+    let analyzer = UsageAnalyzer::new(Arc::clone(&state.db), Arc::clone(&state.graphs));
+    analyzer.find_dependency_cycles(&project)
+}
+
+#[tauri::command]
+pub async fn library_get_dependency_paths(
+    root_id: String,
+    target_id: String,
+    max_paths: Option<usize>,
+    state: State<'_, LibraryState>,
+) -> Result<DependencyPathReport, AppError> {
+    let root = state
+        .db
+        .get_asset(&root_id)?
+        .ok_or_else(|| AppError::AssetNotFound(root_id))?;
+    let target = state
+        .db
+        .get_asset(&target_id)?
+        .ok_or_else(|| AppError::AssetNotFound(target_id))?;
+
+    let analyzer = UsageAnalyzer::new(Arc::clone(&state.db), Arc::clone(&state.graphs));
+    analyzer.find_dependency_paths(&root, &target, max_paths.unwrap_or(5))
+}
+
+#[tauri::command]
+pub async fn library_get_delete_impact(
+    asset_id: String,
+    max_depth: Option<usize>,
+    min_confidence: Option<String>,
+    state: State<'_, LibraryState>,
+) -> Result<DeleteImpactReport, AppError> {
+    let asset = state
+        .db
+        .get_asset(&asset_id)?
+        .ok_or_else(|| AppError::AssetNotFound(asset_id))?;
+
+    let analyzer = UsageAnalyzer::new(Arc::clone(&state.db), Arc::clone(&state.graphs));
+    analyzer.find_delete_impact(
+        &asset,
+        max_depth.unwrap_or(10),
+        min_confidence.as_deref().unwrap_or(CONFIDENCE_HIGH),
+    )
+}
+
+#[tauri::command]
+pub async fn library_get_script_usages(
+    asset_id: String,
+    state: State<'_, LibraryState>,
+) -> Result<ScriptUsageReport, AppError> {
+    let script = state
+        .db
+        .get_asset(&asset_id)?
+        .ok_or_else(|| AppError::AssetNotFound(asset_id))?;
+    let time_budget = std::time::Duration::from_secs(state.settings.read().parse_time_budget_secs);
+
+    let analyzer = UsageAnalyzer::new(Arc::clone(&state.db), Arc::clone(&state.graphs)).with_time_budget(time_budget);
+    analyzer.find_script_usages(&script)
+}
+
+#[tauri::command]
+pub async fn library_get_missing_scripts(
+    project_id: String,
+    state: State<'_, LibraryState>,
+) -> Result<MissingScriptReport, AppError> {
+    let project = state
+        .db
+        .get_project(&project_id)?
+        .ok_or_else(|| AppError::Custom("Project not found".to_string()))?;
+
+    let analyzer = UsageAnalyzer::new(Arc::clone(&state.db), Arc::clone(&state.graphs))
+        .with_package_cache_indexed(state.settings.read().index_package_cache);
+    analyzer.find_missing_scripts(&project)
+}
+
+#[tauri::command]
+pub async fn library_export_dependency_graph(
+    project_id: String,
+    asset_id: Option<String>,
+    folder_path: Option<String>,
+    max_depth: Option<usize>,
+    format: String,
+    dest_path: String,
+    state: State<'_, LibraryState>,
+) -> Result<GraphExportResult, AppError> {
+    let scope = match (asset_id, folder_path) {
+        (Some(asset_id), _) => GraphScope::Asset {
+            asset_id,
+            max_depth: max_depth.unwrap_or(5),
+        },
+        (None, Some(folder)) => GraphScope::Folder(folder),
+        // Neither an asset nor a folder: the whole project
+        (None, None) => GraphScope::Project,
+    };
+
+    let exporter = GraphExporter::new(Arc::clone(&state.db), Arc::clone(&state.graphs));
+    exporter.export(&project_id, &scope, &format, Path::new(&dest_path))
+}
+
 #[tauri::command]
 pub async fn library_export_file(
     asset_id: String,
     dest_folder: String,
+    conflict_policy: Option<String>,
+    dry_run: Option<bool>,
     state: State<'_, LibraryState>,
 ) -> Result<ExportResult, AppError> {
     let asset = state
@@ -516,23 +890,138 @@ pub async fn library_export_file(
         .get_asset(&asset_id)?
         .ok_or_else(|| AppError::AssetNotFound(asset_id))?;
 
-    let exporter = Exporter::new(Arc::clone(&state.db));
+    let policy = conflict_policy.as_deref().map(ConflictPolicy::parse).transpose()?.unwrap_or_default();
+    let exporter = Exporter::new(Arc::clone(&state.db), Arc::clone(&state.graphs)).with_conflict_policy(policy);
+    if dry_run.unwrap_or(false) {
+        return Ok(ExportResult::planned(exporter.plan_file(&asset, Path::new(&dest_folder))?));
+    }
     exporter.export_file(&asset, Path::new(&dest_folder))
 }
 
 #[tauri::command]
 pub async fn library_export_bundle(
-    asset_id: String,
+    asset_ids: Vec<String>,
+    project_id: Option<String>,
+    folder_path: Option<String>,
     dest_folder: String,
+    options: Option<ExportOptions>,
     state: State<'_, LibraryState>,
 ) -> Result<ExportResult, AppError> {
-    let asset = state
+    let options = options.unwrap_or_default();
+    let mut roots = Vec::new();
+    for asset_id in asset_ids {
+        let asset = state
+            .db
+            .get_asset(&asset_id)?
+            .ok_or_else(|| AppError::AssetNotFound(asset_id))?;
+        roots.push(asset);
+    }
+
+    // Everything under a folder joins the selection; an empty folder is the project
+    // root, which covers the project's own assets but not indexed packages
+    if let Some(folder) = folder_path {
+        let project_id = project_id
+            .or_else(|| roots.first().map(|r| r.project_id.clone()))
+            .ok_or_else(|| AppError::Custom("Exporting a folder needs a project or a selected asset".to_string()))?;
+        let folder = normalize_folder_path(&folder);
+        let prefix = if folder.is_empty() { String::new() } else { format!("{}/", folder) };
+        let selected: HashSet<String> = roots.iter().map(|r| r.id.clone()).collect();
+        roots.extend(state.db.get_all_assets(&project_id)?.into_iter().filter(|a| {
+            let path = normalize_folder_path(&a.relative_path);
+            !selected.contains(&a.id)
+                && path.starts_with(&prefix)
+                && (!prefix.is_empty() || asset_scope(&path) == SCOPE_PROJECT)
+        }));
+    }
+
+    let policy = options.conflict_policy.as_deref().map(ConflictPolicy::parse).transpose()?.unwrap_or_default();
+    let exporter = Exporter::new(Arc::clone(&state.db), Arc::clone(&state.graphs))
+        .with_regenerated_guids(options.regenerate_guids.unwrap_or(false))
+        .with_excluded_types(options.exclude_types.unwrap_or_default())
+        .with_conflict_policy(policy)
+        .with_min_confidence(options.min_confidence.as_deref().unwrap_or(CONFIDENCE_HIGH));
+    let dest_folder = Path::new(&dest_folder);
+    let max_depth = options.max_depth.unwrap_or(5);
+    match (options.format.as_deref().unwrap_or("folder"), options.dry_run.unwrap_or(false)) {
+        ("folder", false) => exporter.export_bundle(&roots, dest_folder, max_depth),
+        ("folder", true) => Ok(ExportResult::planned(exporter.plan_bundle(&roots, dest_folder, max_depth)?)),
+        ("unitypackage", false) => exporter.export_unitypackage(&roots, dest_folder, max_depth),
+        ("unitypackage", true) => Ok(ExportResult::planned(exporter.plan_unitypackage(&roots, dest_folder, max_depth)?)),
+        (other, _) => Err(AppError::Custom(format!("Unsupported bundle format: {}", other))),
+    }
+}
+
+#[tauri::command]
+pub async fn library_import_bundle(
+    project_id: String,
+    source_path: String,
+    target_folder: String,
+    dry_run: Option<bool>,
+    state: State<'_, LibraryState>,
+) -> Result<ImportResult, AppError> {
+    let project = state
         .db
-        .get_asset(&asset_id)?
-        .ok_or_else(|| AppError::AssetNotFound(asset_id))?;
+        .get_project(&project_id)?
+        .ok_or_else(|| AppError::Custom("Project not found".to_string()))?;
+
+    // Held for the whole import, so a scan can't race the indexing of the copied files
+    let dry_run = dry_run.unwrap_or(false);
+    if !dry_run && !state.try_claim_scan() {
+        return Err(AppError::Custom("A scan is running; import once it has finished".to_string()));
+    }
+
+    let result = import_and_index(&state, &project, Path::new(&source_path), &target_folder, dry_run);
+    if !dry_run {
+        state.set_scan_running(false);
+    }
+
+    result
+}
+
+fn import_and_index(
+    state: &LibraryState,
+    project: &Project,
+    source_path: &Path,
+    target_folder: &str,
+    dry_run: bool,
+) -> Result<ImportResult, AppError> {
+    let importer = Importer::new(Arc::clone(&state.db));
+    let mut result = importer.import(project, source_path, target_folder, dry_run)?;
+    if result.dry_run || result.imported_files.is_empty() {
+        return Ok(result);
+    }
+
+    // Index what was copied and resolve its references
+    let settings = state.settings.read().clone();
+    let existing_assets = state.db.get_existing_asset_info(&project.id)?;
+    let indexer = Indexer::new(Arc::clone(&state.db)).with_history(true);
+    let mut seen_paths = HashSet::new();
+    let (indexed, _) = scan_subtree(
+        Path::new(&project.root_path),
+        &result.plan.target_folder,
+        ScanOptions {
+            project_id: &project.id,
+            ignore_patterns: &settings.ignore_patterns,
+            batch_size: 25,
+            cancel_flag: Arc::new(AtomicBool::new(false)),
+            existing_assets: Some(&existing_assets),
+        },
+        &mut seen_paths,
+        |batch, _, _| {
+            if let Err(e) = indexer.upsert_batch(&batch) {
+                tracing::error!("Failed to index imported batch: {}", e);
+            }
+            true
+        },
+    )?;
+    result.indexed_count = indexed;
+
+    DependencyResolver::new(Arc::clone(&state.db), Arc::clone(&state.graphs))
+        .with_include_roots(settings.shader_include_roots.clone())
+        .with_time_budget(std::time::Duration::from_secs(settings.parse_time_budget_secs))
+        .resolve_changed_with_progress(&project.id, Arc::new(AtomicBool::new(false)), |_, _| {})?;
 
-    let exporter = Exporter::new(Arc::clone(&state.db));
-    exporter.export_bundle(&asset, Path::new(&dest_folder), 5)
+    Ok(result)
 }
 
 #[tauri::command]
@@ -617,6 +1106,7 @@ pub struct BundleAssetInfo {
 #[tauri::command]
 pub async fn library_get_bundle_preview(
     asset_id: String,
+    min_confidence: Option<String>,
     state: State<'_, LibraryState>,
 ) -> Result<BundlePreview, AppError> {
     let asset = state
@@ -624,8 +1114,9 @@ pub async fn library_get_bundle_preview(
         .get_asset(&asset_id)?
         .ok_or_else(|| AppError::AssetNotFound(asset_id.clone()))?;
 
-    let dep_resolver = DependencyResolver::new(Arc::clone(&state.db));
-    let dep_ids = dep_resolver.get_dependency_tree(&asset_id, 5)?;
+    let dep_resolver = DependencyResolver::new(Arc::clone(&state.db), Arc::clone(&state.graphs));
+    let dep_ids =
+        dep_resolver.get_dependency_tree(&asset_id, 5, min_confidence.as_deref().unwrap_or(CONFIDENCE_HIGH))?;
 
     let mut dependencies = Vec::new();
     let mut total_size = asset.size_bytes;
@@ -1,4 +1,6 @@
+use crate::library::deps::{parseable_extensions_sql, MONO_SCRIPT_FILE_ID};
 use crate::library::error::AppResult;
+use crate::library::packages::{asset_scope, SCOPE_PACKAGE};
 use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
 use rusqlite::params;
@@ -41,6 +43,12 @@ impl Database {
     fn init_schema(&self) -> AppResult<()> {
         let conn = self.pool.get()?;
 
+        let parse_warnings_exist: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type='table' AND name='library_parse_warnings')",
+            [],
+            |row| row.get(0),
+        )?;
+
         conn.execute_batch(
             r#"
             -- Projects table
@@ -106,6 +114,71 @@ impl Database {
             CREATE INDEX IF NOT EXISTS idx_library_deps_to ON library_dependencies(to_asset_id);
             CREATE INDEX IF NOT EXISTS idx_library_deps_guid ON library_dependencies(to_guid);
 
+            -- Per-asset change history recorded by scans
+            CREATE TABLE IF NOT EXISTS library_asset_history (
+                id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                asset_id TEXT NOT NULL,
+                change_type TEXT NOT NULL,
+                relative_path TEXT NOT NULL,
+                old_path TEXT,
+                old_size_bytes INTEGER,
+                new_size_bytes INTEGER,
+                old_modified_time INTEGER,
+                new_modified_time INTEGER,
+                old_content_hash TEXT,
+                new_content_hash TEXT,
+                detected_at INTEGER NOT NULL,
+                FOREIGN KEY (project_id) REFERENCES library_projects(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_library_history_asset ON library_asset_history(asset_id, detected_at);
+            CREATE INDEX IF NOT EXISTS idx_library_history_project ON library_asset_history(project_id, detected_at);
+
+            -- Notes recorded while parsing an asset for dependencies
+            CREATE TABLE IF NOT EXISTS library_parse_warnings (
+                id TEXT PRIMARY KEY,
+                asset_id TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                message TEXT NOT NULL,
+                detected_at INTEGER NOT NULL,
+                FOREIGN KEY (asset_id) REFERENCES library_assets(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_library_parse_warnings_asset ON library_parse_warnings(asset_id);
+
+            -- Paths an asset names (includes, data paths) that matched nothing when it was parsed
+            CREATE TABLE IF NOT EXISTS library_unresolved_paths (
+                asset_id TEXT NOT NULL,
+                relative_path TEXT NOT NULL,
+                PRIMARY KEY (asset_id, relative_path),
+                FOREIGN KEY (asset_id) REFERENCES library_assets(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_library_unresolved_paths_path ON library_unresolved_paths(relative_path);
+
+            -- Addressables group membership, rebuilt from the group assets on each scan
+            CREATE TABLE IF NOT EXISTS library_addressables (
+                asset_id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                entry_guid TEXT NOT NULL,
+                address TEXT NOT NULL,
+                group_name TEXT NOT NULL,
+                from_folder INTEGER NOT NULL DEFAULT 0,
+                FOREIGN KEY (asset_id) REFERENCES library_assets(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_library_addressables_group ON library_addressables(project_id, group_name);
+
+            CREATE TABLE IF NOT EXISTS library_addressable_labels (
+                asset_id TEXT NOT NULL,
+                label TEXT NOT NULL,
+                PRIMARY KEY (asset_id, label),
+                FOREIGN KEY (asset_id) REFERENCES library_assets(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_library_addressable_labels_label ON library_addressable_labels(label);
+
             -- Preview cache tracking
             CREATE TABLE IF NOT EXISTS library_preview_cache (
                 asset_id TEXT PRIMARY KEY,
@@ -125,6 +198,43 @@ impl Database {
             |row| row.get(0),
         )?;
 
+        // Columns added after the initial schema
+        add_column_if_missing(&conn, "library_assets", "parent_path", "TEXT")?;
+        add_column_if_missing(&conn, "library_assets", "deps_modified_time", "INTEGER")?;
+        let mut reference_columns_added = false;
+        for (column, definition) in [
+            ("to_file_id", "INTEGER"),
+            ("reference_type", "INTEGER"),
+            ("sub_asset_name", "TEXT"),
+        ] {
+            reference_columns_added |= add_column_if_missing(&conn, "library_dependencies", column, definition)?;
+        }
+        add_column_if_missing(&conn, "library_assets", "scope", "TEXT NOT NULL DEFAULT 'project'")?;
+        conn.execute_batch(
+            "CREATE INDEX IF NOT EXISTS idx_library_assets_project_parent ON library_assets(project_id, parent_path);",
+        )?;
+        backfill_parent_paths(&conn)?;
+
+        // One-shot data migrations, tracked in `PRAGMA user_version`
+        let user_version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if user_version < 1 {
+            // `.mtl` files were indexed as unknown, then briefly as materials
+            conn.execute(
+                r#"
+                UPDATE library_assets SET asset_type = 'model', thumbnail_path = NULL
+                WHERE LOWER(extension) = 'mtl' AND asset_type IN ('unknown', 'material')
+                "#,
+                [],
+            )?;
+            conn.execute_batch("PRAGMA user_version = 1;")?;
+        }
+
+        // Binary serialized assets used to resolve to nothing, and edges stored before
+        // the reference columns existed lack fileIDs and script usage; re-parse everything once
+        if !parse_warnings_exist || reference_columns_added {
+            conn.execute("UPDATE library_assets SET deps_modified_time = NULL", [])?;
+        }
+
         if !trigger_exists {
             conn.execute_batch(
                 r#"
@@ -152,6 +262,141 @@ impl Database {
     }
 }
 
+/// Add `column` unless the table already has it. Returns whether it was added.
+fn add_column_if_missing(
+    conn: &rusqlite::Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> AppResult<bool> {
+    let exists: bool = conn.query_row(
+        &format!("SELECT EXISTS(SELECT 1 FROM pragma_table_info('{}') WHERE name = ?1)", table),
+        params![column],
+        |row| row.get(0),
+    )?;
+
+    if !exists {
+        conn.execute_batch(&format!("ALTER TABLE {} ADD COLUMN {} {};", table, column, definition))?;
+    }
+
+    Ok(!exists)
+}
+
+/// Insert or update an asset row on `conn`, so callers can batch it in their own transaction.
+pub fn upsert_asset_row(conn: &rusqlite::Connection, asset: &Asset) -> AppResult<()> {
+    conn.execute(
+        r#"
+        INSERT INTO library_assets (id, project_id, absolute_path, relative_path, file_name, extension,
+                           asset_type, size_bytes, modified_time, content_hash, unity_guid,
+                           import_type, thumbnail_path, created_at, updated_at, parent_path, scope)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)
+        ON CONFLICT(project_id, relative_path) DO UPDATE SET
+            absolute_path = excluded.absolute_path,
+            file_name = excluded.file_name,
+            extension = excluded.extension,
+            asset_type = excluded.asset_type,
+            size_bytes = excluded.size_bytes,
+            modified_time = excluded.modified_time,
+            content_hash = excluded.content_hash,
+            unity_guid = excluded.unity_guid,
+            import_type = excluded.import_type,
+            thumbnail_path = excluded.thumbnail_path,
+            updated_at = excluded.updated_at,
+            parent_path = excluded.parent_path,
+            scope = excluded.scope
+        "#,
+        params![
+            asset.id,
+            asset.project_id,
+            asset.absolute_path,
+            asset.relative_path,
+            asset.file_name,
+            asset.extension,
+            asset.asset_type,
+            asset.size_bytes,
+            asset.modified_time,
+            asset.content_hash,
+            asset.unity_guid,
+            asset.import_type,
+            asset.thumbnail_path,
+            asset.created_at,
+            asset.updated_at,
+            parent_folder(&asset.relative_path),
+            asset_scope(&asset.relative_path),
+        ],
+    )?;
+
+    Ok(())
+}
+
+/// Insert a history entry on `conn`, so it can share a transaction with the asset write.
+pub fn insert_history_row(conn: &rusqlite::Connection, entry: &AssetHistoryEntry) -> AppResult<()> {
+    conn.execute(
+        r#"
+        INSERT INTO library_asset_history (id, project_id, asset_id, change_type, relative_path, old_path,
+                                           old_size_bytes, new_size_bytes, old_modified_time, new_modified_time,
+                                           old_content_hash, new_content_hash, detected_at)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+        "#,
+        params![
+            entry.id,
+            entry.project_id,
+            entry.asset_id,
+            entry.change_type,
+            entry.relative_path,
+            entry.old_path,
+            entry.old_size_bytes,
+            entry.new_size_bytes,
+            entry.old_modified_time,
+            entry.new_modified_time,
+            entry.old_content_hash,
+            entry.new_content_hash,
+            entry.detected_at,
+        ],
+    )?;
+
+    Ok(())
+}
+
+fn backfill_parent_paths(conn: &rusqlite::Connection) -> AppResult<()> {
+    let mut stmt = conn.prepare("SELECT id, relative_path FROM library_assets WHERE parent_path IS NULL")?;
+    let rows: Vec<(String, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    if rows.is_empty() {
+        return Ok(());
+    }
+
+    tracing::info!("Backfilling parent_path for {} assets", rows.len());
+    conn.execute("BEGIN TRANSACTION", [])?;
+    for (id, relative_path) in &rows {
+        conn.execute(
+            "UPDATE library_assets SET parent_path = ?1 WHERE id = ?2",
+            params![parent_folder(relative_path), id],
+        )?;
+    }
+    conn.execute("COMMIT", [])?;
+
+    Ok(())
+}
+
+/// Normalize a folder path to forward slashes without leading/trailing separators.
+/// The project root is represented by an empty string.
+pub fn normalize_folder_path(path: &str) -> String {
+    path.replace('\\', "/").trim_matches('/').to_string()
+}
+
+/// Folder containing a relative asset path, normalized with `normalize_folder_path`.
+pub fn parent_folder(relative_path: &str) -> String {
+    let normalized = normalize_folder_path(relative_path);
+    match normalized.rfind('/') {
+        Some(idx) => normalized[..idx].to_string(),
+        None => String::new(),
+    }
+}
+
 // Data structures for database operations
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Project {
@@ -192,6 +437,24 @@ pub struct Dependency {
     pub relation_type: String,
     pub confidence: String,
     pub created_at: i64,
+    /// `fileID` of the referenced object inside the target file (a sprite in an atlas,
+    /// a mesh in an FBX). `None` when the reference only named the GUID.
+    pub to_file_id: Option<i64>,
+    /// Unity reference `type`: 2 for serialized assets, 3 for imported assets.
+    pub reference_type: Option<i32>,
+    /// Sub-asset name resolved from the target's `.meta`, when available.
+    pub sub_asset_name: Option<String>,
+}
+
+/// Filters of an asset listing; unset fields don't filter.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AssetFilter {
+    pub search_query: Option<String>,
+    pub asset_types: Option<Vec<String>>,
+    pub addressable_group: Option<String>,
+    pub addressable_label: Option<String>,
+    /// `project` (the default), `package` or `all`
+    pub scope: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -200,6 +463,80 @@ pub struct TypeCount {
     pub count: i64,
 }
 
+/// One row of an asset's change timeline. `change_type` is one of
+/// `added`, `modified`, `moved` or `deleted`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetHistoryEntry {
+    pub id: String,
+    pub project_id: String,
+    pub asset_id: String,
+    pub change_type: String,
+    pub relative_path: String,
+    pub old_path: Option<String>,
+    pub old_size_bytes: Option<i64>,
+    pub new_size_bytes: Option<i64>,
+    pub old_modified_time: Option<i64>,
+    pub new_modified_time: Option<i64>,
+    pub old_content_hash: Option<String>,
+    pub new_content_hash: Option<String>,
+    pub detected_at: i64,
+}
+
+/// Something notable about how an asset was parsed for dependencies (binary
+/// serialization, unreadable content, ...), joined with the asset's path.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParseWarning {
+    pub asset_id: String,
+    pub relative_path: String,
+    pub file_name: String,
+    pub kind: String,
+    pub message: String,
+    pub detected_at: i64,
+}
+
+/// An asset made addressable by an Addressables group entry, directly or through a
+/// folder entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressableAsset {
+    pub asset_id: String,
+    pub relative_path: String,
+    pub file_name: String,
+    pub asset_type: String,
+    pub size_bytes: i64,
+    /// GUID of the group entry, which is a folder's for `from_folder` assets
+    pub entry_guid: String,
+    pub address: String,
+    pub group_name: String,
+    pub labels: Vec<String>,
+    pub from_folder: bool,
+}
+
+/// A dependency row whose GUID didn't resolve, joined with its referencing asset.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DanglingReference {
+    pub to_guid: String,
+    pub relation_type: String,
+    pub from_asset_id: String,
+    pub from_file_name: String,
+    pub from_relative_path: String,
+    pub from_asset_type: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FolderNode {
+    pub path: String,
+    pub name: String,
+    pub file_count: i64,
+    pub total_size_bytes: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FolderContents {
+    pub path: String,
+    pub folders: Vec<FolderNode>,
+    pub assets: Vec<Asset>,
+}
+
 impl Database {
     pub fn get_or_create_project(&self, root_path: &str, name: &str) -> AppResult<Project> {
         let conn = self.pool.get()?;
@@ -273,6 +610,31 @@ impl Database {
         Ok(result)
     }
 
+    pub fn get_project(&self, project_id: &str) -> AppResult<Option<Project>> {
+        let conn = self.pool.get()?;
+
+        let result = conn
+            .query_row(
+                "SELECT id, root_path, name, last_scan_time, file_count, created_at, updated_at
+                 FROM library_projects WHERE id = ?1",
+                params![project_id],
+                |row| {
+                    Ok(Project {
+                        id: row.get(0)?,
+                        root_path: row.get(1)?,
+                        name: row.get(2)?,
+                        last_scan_time: row.get(3)?,
+                        file_count: row.get(4)?,
+                        created_at: row.get(5)?,
+                        updated_at: row.get(6)?,
+                    })
+                },
+            )
+            .ok();
+
+        Ok(result)
+    }
+
     pub fn update_project_scan_time(&self, project_id: &str, file_count: i64) -> AppResult<()> {
         let conn = self.pool.get()?;
         let now = chrono::Utc::now().timestamp();
@@ -287,73 +649,34 @@ impl Database {
 
     pub fn upsert_asset(&self, asset: &Asset) -> AppResult<()> {
         let conn = self.pool.get()?;
-
-        conn.execute(
-            r#"
-            INSERT INTO library_assets (id, project_id, absolute_path, relative_path, file_name, extension,
-                               asset_type, size_bytes, modified_time, content_hash, unity_guid,
-                               import_type, thumbnail_path, created_at, updated_at)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
-            ON CONFLICT(project_id, relative_path) DO UPDATE SET
-                absolute_path = excluded.absolute_path,
-                file_name = excluded.file_name,
-                extension = excluded.extension,
-                asset_type = excluded.asset_type,
-                size_bytes = excluded.size_bytes,
-                modified_time = excluded.modified_time,
-                content_hash = excluded.content_hash,
-                unity_guid = excluded.unity_guid,
-                import_type = excluded.import_type,
-                thumbnail_path = excluded.thumbnail_path,
-                updated_at = excluded.updated_at
-            "#,
-            params![
-                asset.id,
-                asset.project_id,
-                asset.absolute_path,
-                asset.relative_path,
-                asset.file_name,
-                asset.extension,
-                asset.asset_type,
-                asset.size_bytes,
-                asset.modified_time,
-                asset.content_hash,
-                asset.unity_guid,
-                asset.import_type,
-                asset.thumbnail_path,
-                asset.created_at,
-                asset.updated_at,
-            ],
-        )?;
-
-        Ok(())
+        upsert_asset_row(&conn, asset)
     }
 
     pub fn get_assets(
         &self,
         project_id: &str,
-        search_query: Option<&str>,
-        asset_types: Option<&[String]>,
+        filter: &AssetFilter,
         page: i64,
         page_size: i64,
     ) -> AppResult<(Vec<Asset>, i64)> {
         let conn = self.pool.get()?;
         let offset = page * page_size;
+        let search_query = filter.search_query.as_deref();
 
         let (where_clause, count_where) = if let Some(query) = search_query {
             if query.trim().is_empty() {
-                ("WHERE a.project_id = ?1".to_string(), "WHERE project_id = ?1".to_string())
+                ("WHERE a.project_id = :project_id".to_string(), "WHERE project_id = :project_id".to_string())
             } else {
                 (
-                    "WHERE a.project_id = ?1 AND a.rowid IN (SELECT rowid FROM library_assets_fts WHERE library_assets_fts MATCH ?4)".to_string(),
-                    "WHERE project_id = ?1 AND rowid IN (SELECT rowid FROM library_assets_fts WHERE library_assets_fts MATCH ?2)".to_string(),
+                    "WHERE a.project_id = :project_id AND a.rowid IN (SELECT rowid FROM library_assets_fts WHERE library_assets_fts MATCH :query)".to_string(),
+                    "WHERE project_id = :project_id AND rowid IN (SELECT rowid FROM library_assets_fts WHERE library_assets_fts MATCH :query)".to_string(),
                 )
             }
         } else {
-            ("WHERE a.project_id = ?1".to_string(), "WHERE project_id = ?1".to_string())
+            ("WHERE a.project_id = :project_id".to_string(), "WHERE project_id = :project_id".to_string())
         };
 
-        let type_filter = if let Some(types) = asset_types {
+        let mut type_filter = if let Some(types) = &filter.asset_types {
             if types.is_empty() {
                 String::new()
             } else {
@@ -364,22 +687,39 @@ impl Database {
             String::new()
         };
 
+        // Package assets are only listed when asked for
+        match filter.scope.as_deref() {
+            Some("all") => {}
+            Some(SCOPE_PACKAGE) => type_filter.push_str(" AND scope = 'package'"),
+            _ => type_filter.push_str(" AND scope = 'project'"),
+        }
+
+        let mut filter_bindings: Vec<(&str, &dyn rusqlite::ToSql)> = Vec::new();
+        if let Some(group) = &filter.addressable_group {
+            type_filter.push_str(" AND id IN (SELECT asset_id FROM library_addressables WHERE group_name = :group)");
+            filter_bindings.push((":group", group));
+        }
+        if let Some(label) = &filter.addressable_label {
+            type_filter.push_str(" AND id IN (SELECT asset_id FROM library_addressable_labels WHERE label = :label)");
+            filter_bindings.push((":label", label));
+        }
+
+        let fts_query = search_query
+            .filter(|query| !query.trim().is_empty())
+            .map(|query| format!("{}*", query));
+
         // Get total count
         let count_sql = format!(
             "SELECT COUNT(*) FROM library_assets {}{}",
             count_where, type_filter
         );
 
-        let total: i64 = if let Some(query) = search_query {
-            if query.trim().is_empty() {
-                conn.query_row(&count_sql, params![project_id], |row| row.get(0))?
-            } else {
-                let fts_query = format!("{}*", query);
-                conn.query_row(&count_sql, params![project_id, fts_query], |row| row.get(0))?
-            }
-        } else {
-            conn.query_row(&count_sql, params![project_id], |row| row.get(0))?
-        };
+        let mut count_bindings: Vec<(&str, &dyn rusqlite::ToSql)> = vec![(":project_id", &project_id)];
+        if let Some(fts_query) = &fts_query {
+            count_bindings.push((":query", fts_query));
+        }
+        count_bindings.extend(filter_bindings.iter().copied());
+        let total: i64 = conn.query_row(&count_sql, count_bindings.as_slice(), |row| row.get(0))?;
 
         // Get assets
         let sql = format!(
@@ -390,62 +730,22 @@ impl Database {
             FROM library_assets a
             {}{}
             ORDER BY a.file_name ASC
-            LIMIT ?2 OFFSET ?3
+            LIMIT :limit OFFSET :offset
             "#,
             where_clause, type_filter
         );
 
         let mut stmt = conn.prepare(&sql)?;
 
-        let assets: Vec<Asset> = if let Some(query) = search_query {
-            if query.trim().is_empty() {
-                stmt.query_map(params![project_id, page_size, offset], |row| {
-                    Ok(Asset {
-                        id: row.get(0)?,
-                        project_id: row.get(1)?,
-                        absolute_path: row.get(2)?,
-                        relative_path: row.get(3)?,
-                        file_name: row.get(4)?,
-                        extension: row.get(5)?,
-                        asset_type: row.get(6)?,
-                        size_bytes: row.get(7)?,
-                        modified_time: row.get(8)?,
-                        content_hash: row.get(9)?,
-                        unity_guid: row.get(10)?,
-                        import_type: row.get(11)?,
-                        thumbnail_path: row.get(12)?,
-                        created_at: row.get(13)?,
-                        updated_at: row.get(14)?,
-                    })
-                })?
-                .filter_map(|r| r.ok())
-                .collect()
-            } else {
-                let fts_query = format!("{}*", query);
-                stmt.query_map(params![project_id, page_size, offset, fts_query], |row| {
-                    Ok(Asset {
-                        id: row.get(0)?,
-                        project_id: row.get(1)?,
-                        absolute_path: row.get(2)?,
-                        relative_path: row.get(3)?,
-                        file_name: row.get(4)?,
-                        extension: row.get(5)?,
-                        asset_type: row.get(6)?,
-                        size_bytes: row.get(7)?,
-                        modified_time: row.get(8)?,
-                        content_hash: row.get(9)?,
-                        unity_guid: row.get(10)?,
-                        import_type: row.get(11)?,
-                        thumbnail_path: row.get(12)?,
-                        created_at: row.get(13)?,
-                        updated_at: row.get(14)?,
-                    })
-                })?
-                .filter_map(|r| r.ok())
-                .collect()
-            }
-        } else {
-            stmt.query_map(params![project_id, page_size, offset], |row| {
+        let mut bindings: Vec<(&str, &dyn rusqlite::ToSql)> =
+            vec![(":project_id", &project_id), (":limit", &page_size), (":offset", &offset)];
+        if let Some(fts_query) = &fts_query {
+            bindings.push((":query", fts_query));
+        }
+        bindings.extend(filter_bindings.iter().copied());
+
+        let assets: Vec<Asset> = stmt
+            .query_map(bindings.as_slice(), |row| {
                 Ok(Asset {
                     id: row.get(0)?,
                     project_id: row.get(1)?,
@@ -465,8 +765,7 @@ impl Database {
                 })
             })?
             .filter_map(|r| r.ok())
-            .collect()
-        };
+            .collect();
 
         Ok((assets, total))
     }
@@ -545,11 +844,48 @@ impl Database {
         Ok(result)
     }
 
+    pub fn get_asset_by_relative_path(&self, project_id: &str, relative_path: &str) -> AppResult<Option<Asset>> {
+        let conn = self.pool.get()?;
+
+        let result = conn
+            .query_row(
+                r#"
+                SELECT id, project_id, absolute_path, relative_path, file_name, extension,
+                       asset_type, size_bytes, modified_time, content_hash, unity_guid,
+                       import_type, thumbnail_path, created_at, updated_at
+                FROM library_assets WHERE project_id = ?1 AND relative_path = ?2
+                "#,
+                params![project_id, relative_path],
+                |row| {
+                    Ok(Asset {
+                        id: row.get(0)?,
+                        project_id: row.get(1)?,
+                        absolute_path: row.get(2)?,
+                        relative_path: row.get(3)?,
+                        file_name: row.get(4)?,
+                        extension: row.get(5)?,
+                        asset_type: row.get(6)?,
+                        size_bytes: row.get(7)?,
+                        modified_time: row.get(8)?,
+                        content_hash: row.get(9)?,
+                        unity_guid: row.get(10)?,
+                        import_type: row.get(11)?,
+                        thumbnail_path: row.get(12)?,
+                        created_at: row.get(13)?,
+                        updated_at: row.get(14)?,
+                    })
+                },
+            )
+            .ok();
+
+        Ok(result)
+    }
+
     pub fn get_type_counts(&self, project_id: &str) -> AppResult<Vec<TypeCount>> {
         let conn = self.pool.get()?;
 
         let mut stmt = conn.prepare(
-            "SELECT asset_type, COUNT(*) as count FROM library_assets WHERE project_id = ?1 GROUP BY asset_type",
+            "SELECT asset_type, COUNT(*) as count FROM library_assets WHERE project_id = ?1 AND scope = 'project' GROUP BY asset_type",
         )?;
 
         let counts: Vec<TypeCount> = stmt
@@ -570,8 +906,9 @@ impl Database {
 
         conn.execute(
             r#"
-            INSERT OR REPLACE INTO library_dependencies (id, from_asset_id, to_asset_id, to_guid, relation_type, confidence, created_at)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            INSERT OR REPLACE INTO library_dependencies (id, from_asset_id, to_asset_id, to_guid, relation_type, confidence, created_at,
+                                                         to_file_id, reference_type, sub_asset_name)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
             "#,
             params![
                 dep.id,
@@ -581,6 +918,9 @@ impl Database {
                 dep.relation_type,
                 dep.confidence,
                 dep.created_at,
+                dep.to_file_id,
+                dep.reference_type,
+                dep.sub_asset_name,
             ],
         )?;
 
@@ -591,7 +931,8 @@ impl Database {
         let conn = self.pool.get()?;
 
         let mut stmt = conn.prepare(
-            "SELECT id, from_asset_id, to_asset_id, to_guid, relation_type, confidence, created_at
+            "SELECT id, from_asset_id, to_asset_id, to_guid, relation_type, confidence, created_at,
+                    to_file_id, reference_type, sub_asset_name
              FROM library_dependencies WHERE from_asset_id = ?1",
         )?;
 
@@ -605,6 +946,9 @@ impl Database {
                     relation_type: row.get(4)?,
                     confidence: row.get(5)?,
                     created_at: row.get(6)?,
+                    to_file_id: row.get(7)?,
+                    reference_type: row.get(8)?,
+                    sub_asset_name: row.get(9)?,
                 })
             })?
             .filter_map(|r| r.ok())
@@ -617,7 +961,8 @@ impl Database {
         let conn = self.pool.get()?;
 
         let mut stmt = conn.prepare(
-            "SELECT id, from_asset_id, to_asset_id, to_guid, relation_type, confidence, created_at
+            "SELECT id, from_asset_id, to_asset_id, to_guid, relation_type, confidence, created_at,
+                    to_file_id, reference_type, sub_asset_name
              FROM library_dependencies WHERE to_asset_id = ?1",
         )?;
 
@@ -631,6 +976,9 @@ impl Database {
                     relation_type: row.get(4)?,
                     confidence: row.get(5)?,
                     created_at: row.get(6)?,
+                    to_file_id: row.get(7)?,
+                    reference_type: row.get(8)?,
+                    sub_asset_name: row.get(9)?,
                 })
             })?
             .filter_map(|r| r.ok())
@@ -645,6 +993,16 @@ impl Database {
         Ok(())
     }
 
+    /// Remember which `modified_time` an asset's dependencies were parsed from.
+    pub fn mark_dependencies_resolved(&self, asset_id: &str, modified_time: i64) -> AppResult<()> {
+        let conn = self.pool.get()?;
+        conn.execute(
+            "UPDATE library_assets SET deps_modified_time = ?1 WHERE id = ?2",
+            params![modified_time, asset_id],
+        )?;
+        Ok(())
+    }
+
     pub fn update_asset_thumbnail(&self, asset_id: &str, thumbnail_path: &str) -> AppResult<()> {
         let conn = self.pool.get()?;
         conn.execute(
@@ -664,6 +1022,7 @@ impl Database {
                    import_type, thumbnail_path, created_at, updated_at
             FROM library_assets
             WHERE project_id = ?1
+              AND scope = 'project'
               AND asset_type IN ('texture', 'material')
               AND thumbnail_path IS NULL
             ORDER BY
@@ -706,7 +1065,7 @@ impl Database {
         let conn = self.pool.get()?;
 
         let count: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM library_assets WHERE project_id = ?1 AND asset_type IN ('texture', 'material')",
+            "SELECT COUNT(*) FROM library_assets WHERE project_id = ?1 AND scope = 'project' AND asset_type IN ('texture', 'material')",
             params![project_id],
             |row| row.get(0),
         )?;
@@ -734,7 +1093,7 @@ impl Database {
                    asset_type, size_bytes, modified_time, content_hash, unity_guid,
                    import_type, thumbnail_path, created_at, updated_at
             FROM library_assets
-            WHERE project_id = ?1 AND asset_type = 'model'
+            WHERE project_id = ?1 AND scope = 'project' AND asset_type = 'model'
             "#,
         )?;
 
@@ -797,16 +1156,20 @@ impl Database {
     pub fn get_parseable_assets(&self, project_id: &str) -> AppResult<Vec<Asset>> {
         let conn = self.pool.get()?;
 
-        let mut stmt = conn.prepare(
+        let sql = format!(
             r#"
             SELECT id, project_id, absolute_path, relative_path, file_name, extension,
                    asset_type, size_bytes, modified_time, content_hash, unity_guid,
                    import_type, thumbnail_path, created_at, updated_at
             FROM library_assets
             WHERE project_id = ?1
-              AND asset_type IN ('material', 'prefab', 'scene', 'scriptable_object')
+              AND scope = 'project'
+              AND LOWER(extension) IN ({})
             "#,
-        )?;
+            parseable_extensions_sql()
+        );
+
+        let mut stmt = conn.prepare(&sql)?;
 
         let assets: Vec<Asset> = stmt
             .query_map(params![project_id], |row| {
@@ -833,4 +1196,824 @@ impl Database {
 
         Ok(assets)
     }
+
+    /// Parseable assets that are new or changed since their dependencies were last resolved.
+    pub fn get_assets_with_stale_dependencies(&self, project_id: &str) -> AppResult<Vec<Asset>> {
+        let conn = self.pool.get()?;
+
+        let sql = format!(
+            r#"
+            SELECT id, project_id, absolute_path, relative_path, file_name, extension,
+                   asset_type, size_bytes, modified_time, content_hash, unity_guid,
+                   import_type, thumbnail_path, created_at, updated_at
+            FROM library_assets
+            WHERE project_id = ?1
+              AND scope = 'project'
+              AND LOWER(extension) IN ({})
+              AND (deps_modified_time IS NULL OR deps_modified_time != modified_time)
+            "#,
+            parseable_extensions_sql()
+        );
+
+        let mut stmt = conn.prepare(&sql)?;
+
+        let assets: Vec<Asset> = stmt
+            .query_map(params![project_id], |row| {
+                Ok(Asset {
+                    id: row.get(0)?,
+                    project_id: row.get(1)?,
+                    absolute_path: row.get(2)?,
+                    relative_path: row.get(3)?,
+                    file_name: row.get(4)?,
+                    extension: row.get(5)?,
+                    asset_type: row.get(6)?,
+                    size_bytes: row.get(7)?,
+                    modified_time: row.get(8)?,
+                    content_hash: row.get(9)?,
+                    unity_guid: row.get(10)?,
+                    import_type: row.get(11)?,
+                    thumbnail_path: row.get(12)?,
+                    created_at: row.get(13)?,
+                    updated_at: row.get(14)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(assets)
+    }
+
+    /// Immediate child folders of `folder_path` (with recursive file counts and sizes)
+    /// plus the assets that live directly inside it.
+    pub fn get_folder_contents(&self, project_id: &str, folder_path: &str) -> AppResult<FolderContents> {
+        let conn = self.pool.get()?;
+        let folder = normalize_folder_path(folder_path);
+
+        // Range scan over parent_path keeps this on the (project_id, parent_path) index.
+        // '0' is the character right after '/', so [folder/, folder0) covers every descendant.
+        let (lower, upper) = if folder.is_empty() {
+            (String::new(), None)
+        } else {
+            (format!("{}/", folder), Some(format!("{}0", folder)))
+        };
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT parent_path, COUNT(*), COALESCE(SUM(size_bytes), 0)
+            FROM library_assets
+            WHERE project_id = ?1
+              AND scope = 'project'
+              AND parent_path > ?2
+              AND (?3 IS NULL OR parent_path < ?3)
+            GROUP BY parent_path
+            "#,
+        )?;
+
+        let rows: Vec<(String, i64, i64)> = stmt
+            .query_map(params![project_id, lower, upper], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        let mut children: std::collections::BTreeMap<String, (i64, i64)> = std::collections::BTreeMap::new();
+        for (parent_path, count, size) in rows {
+            let rest = &parent_path[lower.len()..];
+            let child = rest.split('/').next().unwrap_or(rest);
+            if child.is_empty() {
+                continue;
+            }
+            let entry = children.entry(child.to_string()).or_insert((0, 0));
+            entry.0 += count;
+            entry.1 += size;
+        }
+
+        let folders = children
+            .into_iter()
+            .map(|(name, (file_count, total_size_bytes))| FolderNode {
+                path: format!("{}{}", lower, name),
+                name,
+                file_count,
+                total_size_bytes,
+            })
+            .collect();
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT id, project_id, absolute_path, relative_path, file_name, extension,
+                   asset_type, size_bytes, modified_time, content_hash, unity_guid,
+                   import_type, thumbnail_path, created_at, updated_at
+            FROM library_assets
+            WHERE project_id = ?1 AND scope = 'project' AND parent_path = ?2
+            ORDER BY file_name ASC
+            "#,
+        )?;
+
+        let assets: Vec<Asset> = stmt
+            .query_map(params![project_id, folder], |row| {
+                Ok(Asset {
+                    id: row.get(0)?,
+                    project_id: row.get(1)?,
+                    absolute_path: row.get(2)?,
+                    relative_path: row.get(3)?,
+                    file_name: row.get(4)?,
+                    extension: row.get(5)?,
+                    asset_type: row.get(6)?,
+                    size_bytes: row.get(7)?,
+                    modified_time: row.get(8)?,
+                    content_hash: row.get(9)?,
+                    unity_guid: row.get(10)?,
+                    import_type: row.get(11)?,
+                    thumbnail_path: row.get(12)?,
+                    created_at: row.get(13)?,
+                    updated_at: row.get(14)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(FolderContents {
+            path: folder,
+            folders,
+            assets,
+        })
+    }
+
+    pub fn insert_history_entry(&self, entry: &AssetHistoryEntry) -> AppResult<()> {
+        let conn = self.pool.get()?;
+        insert_history_row(&conn, entry)
+    }
+
+    pub fn get_asset_history(&self, asset_id: &str) -> AppResult<Vec<AssetHistoryEntry>> {
+        let conn = self.pool.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT id, project_id, asset_id, change_type, relative_path, old_path,
+                   old_size_bytes, new_size_bytes, old_modified_time, new_modified_time,
+                   old_content_hash, new_content_hash, detected_at
+            FROM library_asset_history
+            WHERE asset_id = ?1
+            ORDER BY detected_at DESC
+            "#,
+        )?;
+
+        let entries: Vec<AssetHistoryEntry> = stmt
+            .query_map(params![asset_id], |row| {
+                Ok(AssetHistoryEntry {
+                    id: row.get(0)?,
+                    project_id: row.get(1)?,
+                    asset_id: row.get(2)?,
+                    change_type: row.get(3)?,
+                    relative_path: row.get(4)?,
+                    old_path: row.get(5)?,
+                    old_size_bytes: row.get(6)?,
+                    new_size_bytes: row.get(7)?,
+                    old_modified_time: row.get(8)?,
+                    new_modified_time: row.get(9)?,
+                    old_content_hash: row.get(10)?,
+                    new_content_hash: row.get(11)?,
+                    detected_at: row.get(12)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(entries)
+    }
+
+    /// All history entries recorded for a project at or after `since` (unix seconds).
+    pub fn get_changes_since(&self, project_id: &str, since: i64) -> AppResult<Vec<AssetHistoryEntry>> {
+        let conn = self.pool.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT id, project_id, asset_id, change_type, relative_path, old_path,
+                   old_size_bytes, new_size_bytes, old_modified_time, new_modified_time,
+                   old_content_hash, new_content_hash, detected_at
+            FROM library_asset_history
+            WHERE project_id = ?1 AND detected_at >= ?2
+            ORDER BY detected_at DESC
+            "#,
+        )?;
+
+        let entries: Vec<AssetHistoryEntry> = stmt
+            .query_map(params![project_id, since], |row| {
+                Ok(AssetHistoryEntry {
+                    id: row.get(0)?,
+                    project_id: row.get(1)?,
+                    asset_id: row.get(2)?,
+                    change_type: row.get(3)?,
+                    relative_path: row.get(4)?,
+                    old_path: row.get(5)?,
+                    old_size_bytes: row.get(6)?,
+                    new_size_bytes: row.get(7)?,
+                    old_modified_time: row.get(8)?,
+                    new_modified_time: row.get(9)?,
+                    old_content_hash: row.get(10)?,
+                    new_content_hash: row.get(11)?,
+                    detected_at: row.get(12)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(entries)
+    }
+
+    /// Find another asset in the project carrying `guid`, used to detect moves.
+    pub fn find_asset_by_guid_excluding(
+        &self,
+        project_id: &str,
+        guid: &str,
+        exclude_id: &str,
+    ) -> AppResult<Option<Asset>> {
+        let conn = self.pool.get()?;
+
+        let result = conn
+            .query_row(
+                r#"
+                SELECT id, project_id, absolute_path, relative_path, file_name, extension,
+                       asset_type, size_bytes, modified_time, content_hash, unity_guid,
+                       import_type, thumbnail_path, created_at, updated_at
+                FROM library_assets WHERE project_id = ?1 AND unity_guid = ?2 AND id != ?3
+                "#,
+                params![project_id, guid, exclude_id],
+                |row| {
+                    Ok(Asset {
+                        id: row.get(0)?,
+                        project_id: row.get(1)?,
+                        absolute_path: row.get(2)?,
+                        relative_path: row.get(3)?,
+                        file_name: row.get(4)?,
+                        extension: row.get(5)?,
+                        asset_type: row.get(6)?,
+                        size_bytes: row.get(7)?,
+                        modified_time: row.get(8)?,
+                        content_hash: row.get(9)?,
+                        unity_guid: row.get(10)?,
+                        import_type: row.get(11)?,
+                        thumbnail_path: row.get(12)?,
+                        created_at: row.get(13)?,
+                        updated_at: row.get(14)?,
+                    })
+                },
+            )
+            .ok();
+
+        Ok(result)
+    }
+
+    /// Carry history and incoming dependency edges over from a moved asset's old row.
+    pub fn transfer_asset_identity(&self, old_id: &str, new_id: &str) -> AppResult<()> {
+        let conn = self.pool.get()?;
+        conn.execute(
+            "UPDATE library_asset_history SET asset_id = ?1 WHERE asset_id = ?2",
+            params![new_id, old_id],
+        )?;
+        conn.execute(
+            "UPDATE library_dependencies SET to_asset_id = ?1 WHERE to_asset_id = ?2",
+            params![new_id, old_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_added_history(&self, asset_id: &str) -> AppResult<()> {
+        let conn = self.pool.get()?;
+        conn.execute(
+            "DELETE FROM library_asset_history WHERE asset_id = ?1 AND change_type = 'added'",
+            params![asset_id],
+        )?;
+        Ok(())
+    }
+
+    /// Remove an asset row that no longer exists on disk. Foreign keys are not enforced,
+    /// so outgoing edges are deleted and incoming edges are detached explicitly.
+    pub fn remove_asset(&self, asset_id: &str) -> AppResult<()> {
+        let conn = self.pool.get()?;
+        conn.execute("DELETE FROM library_dependencies WHERE from_asset_id = ?1", params![asset_id])?;
+        conn.execute(
+            "UPDATE library_dependencies SET to_asset_id = NULL WHERE to_asset_id = ?1",
+            params![asset_id],
+        )?;
+        conn.execute("DELETE FROM library_preview_cache WHERE asset_id = ?1", params![asset_id])?;
+        conn.execute("DELETE FROM library_parse_warnings WHERE asset_id = ?1", params![asset_id])?;
+        conn.execute("DELETE FROM library_unresolved_paths WHERE asset_id = ?1", params![asset_id])?;
+        conn.execute("DELETE FROM library_addressables WHERE asset_id = ?1", params![asset_id])?;
+        conn.execute("DELETE FROM library_addressable_labels WHERE asset_id = ?1", params![asset_id])?;
+        conn.execute("DELETE FROM library_assets WHERE id = ?1", params![asset_id])?;
+        Ok(())
+    }
+
+    /// GUIDs of the indexed `Library/PackageCache` assets.
+    pub fn get_package_guids(&self, project_id: &str) -> AppResult<std::collections::HashSet<String>> {
+        let conn = self.pool.get()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT unity_guid FROM library_assets WHERE project_id = ?1 AND scope = 'package' AND unity_guid IS NOT NULL",
+        )?;
+
+        let guids = stmt
+            .query_map(params![project_id], |row| row.get::<_, String>(0))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(guids)
+    }
+
+    pub fn get_all_assets(&self, project_id: &str) -> AppResult<Vec<Asset>> {
+        let conn = self.pool.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT id, project_id, absolute_path, relative_path, file_name, extension,
+                   asset_type, size_bytes, modified_time, content_hash, unity_guid,
+                   import_type, thumbnail_path, created_at, updated_at
+            FROM library_assets
+            WHERE project_id = ?1
+            "#,
+        )?;
+
+        let assets: Vec<Asset> = stmt
+            .query_map(params![project_id], |row| {
+                Ok(Asset {
+                    id: row.get(0)?,
+                    project_id: row.get(1)?,
+                    absolute_path: row.get(2)?,
+                    relative_path: row.get(3)?,
+                    file_name: row.get(4)?,
+                    extension: row.get(5)?,
+                    asset_type: row.get(6)?,
+                    size_bytes: row.get(7)?,
+                    modified_time: row.get(8)?,
+                    content_hash: row.get(9)?,
+                    unity_guid: row.get(10)?,
+                    import_type: row.get(11)?,
+                    thumbnail_path: row.get(12)?,
+                    created_at: row.get(13)?,
+                    updated_at: row.get(14)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(assets)
+    }
+
+    /// Every dependency edge whose source asset belongs to the project.
+    pub fn get_project_dependencies(&self, project_id: &str) -> AppResult<Vec<Dependency>> {
+        let conn = self.pool.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT d.id, d.from_asset_id, d.to_asset_id, d.to_guid, d.relation_type, d.confidence, d.created_at,
+                   d.to_file_id, d.reference_type, d.sub_asset_name
+            FROM library_dependencies d
+            JOIN library_assets a ON a.id = d.from_asset_id
+            WHERE a.project_id = ?1
+            "#,
+        )?;
+
+        let deps: Vec<Dependency> = stmt
+            .query_map(params![project_id], |row| {
+                Ok(Dependency {
+                    id: row.get(0)?,
+                    from_asset_id: row.get(1)?,
+                    to_asset_id: row.get(2)?,
+                    to_guid: row.get(3)?,
+                    relation_type: row.get(4)?,
+                    confidence: row.get(5)?,
+                    created_at: row.get(6)?,
+                    to_file_id: row.get(7)?,
+                    reference_type: row.get(8)?,
+                    sub_asset_name: row.get(9)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(deps)
+    }
+
+    /// One row per asset and missing GUID, however many objects in the asset point at it.
+    pub fn get_dangling_references(&self, project_id: &str) -> AppResult<Vec<DanglingReference>> {
+        let conn = self.pool.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT d.to_guid, MIN(d.relation_type), a.id, a.file_name, a.relative_path, a.asset_type
+            FROM library_dependencies d
+            JOIN library_assets a ON a.id = d.from_asset_id
+            WHERE a.project_id = ?1 AND d.to_asset_id IS NULL AND d.to_guid IS NOT NULL
+            GROUP BY d.to_guid, a.id
+            ORDER BY a.relative_path ASC
+            "#,
+        )?;
+
+        let refs: Vec<DanglingReference> = stmt
+            .query_map(params![project_id], |row| {
+                Ok(DanglingReference {
+                    to_guid: row.get(0)?,
+                    relation_type: row.get(1)?,
+                    from_asset_id: row.get(2)?,
+                    from_file_name: row.get(3)?,
+                    from_relative_path: row.get(4)?,
+                    from_asset_type: row.get(5)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(refs)
+    }
+
+    /// Replace a project's Addressables membership with `assets`.
+    pub fn replace_addressables(&self, project_id: &str, assets: &[AddressableAsset]) -> AppResult<()> {
+        let conn = self.pool.get()?;
+        conn.execute("BEGIN TRANSACTION", [])?;
+
+        conn.execute(
+            "DELETE FROM library_addressable_labels WHERE asset_id IN (SELECT asset_id FROM library_addressables WHERE project_id = ?1)",
+            params![project_id],
+        )?;
+        conn.execute("DELETE FROM library_addressables WHERE project_id = ?1", params![project_id])?;
+
+        for asset in assets {
+            conn.execute(
+                r#"
+                INSERT OR REPLACE INTO library_addressables (asset_id, project_id, entry_guid, address, group_name, from_folder)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                "#,
+                params![
+                    asset.asset_id,
+                    project_id,
+                    asset.entry_guid,
+                    asset.address,
+                    asset.group_name,
+                    asset.from_folder,
+                ],
+            )?;
+            for label in &asset.labels {
+                conn.execute(
+                    "INSERT OR IGNORE INTO library_addressable_labels (asset_id, label) VALUES (?1, ?2)",
+                    params![asset.asset_id, label],
+                )?;
+            }
+        }
+
+        conn.execute("COMMIT", [])?;
+        Ok(())
+    }
+
+    /// Addressable assets of a project, optionally only those in `group` or carrying `label`.
+    pub fn get_addressables(
+        &self,
+        project_id: &str,
+        group: Option<&str>,
+        label: Option<&str>,
+    ) -> AppResult<Vec<AddressableAsset>> {
+        let conn = self.pool.get()?;
+
+        let mut labels: std::collections::HashMap<String, Vec<String>> = std::collections::HashMap::new();
+        {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT l.asset_id, l.label
+                FROM library_addressable_labels l
+                JOIN library_addressables ad ON ad.asset_id = l.asset_id
+                WHERE ad.project_id = ?1
+                ORDER BY l.label ASC
+                "#,
+            )?;
+            let rows = stmt
+                .query_map(params![project_id], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+                .filter_map(|r| r.ok());
+            for (asset_id, label) in rows {
+                labels.entry(asset_id).or_default().push(label);
+            }
+        }
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT ad.asset_id, a.relative_path, a.file_name, a.asset_type, a.size_bytes,
+                   ad.entry_guid, ad.address, ad.group_name, ad.from_folder
+            FROM library_addressables ad
+            JOIN library_assets a ON a.id = ad.asset_id
+            WHERE ad.project_id = ?1
+              AND (?2 IS NULL OR ad.group_name = ?2)
+              AND (?3 IS NULL OR ad.asset_id IN (SELECT asset_id FROM library_addressable_labels WHERE label = ?3))
+            ORDER BY ad.group_name ASC, a.relative_path ASC
+            "#,
+        )?;
+
+        let assets: Vec<AddressableAsset> = stmt
+            .query_map(params![project_id, group, label], |row| {
+                let asset_id: String = row.get(0)?;
+                Ok(AddressableAsset {
+                    labels: labels.get(&asset_id).cloned().unwrap_or_default(),
+                    asset_id,
+                    relative_path: row.get(1)?,
+                    file_name: row.get(2)?,
+                    asset_type: row.get(3)?,
+                    size_bytes: row.get(4)?,
+                    entry_guid: row.get(5)?,
+                    address: row.get(6)?,
+                    group_name: row.get(7)?,
+                    from_folder: row.get(8)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(assets)
+    }
+
+    /// Replace the parse warnings of an asset with `warnings` (`(kind, message)` pairs).
+    pub fn set_parse_warnings(&self, asset_id: &str, warnings: &[(String, String)]) -> AppResult<()> {
+        let conn = self.pool.get()?;
+        let now = chrono::Utc::now().timestamp();
+
+        conn.execute("DELETE FROM library_parse_warnings WHERE asset_id = ?1", params![asset_id])?;
+        for (kind, message) in warnings {
+            conn.execute(
+                r#"
+                INSERT INTO library_parse_warnings (id, asset_id, kind, message, detected_at)
+                VALUES (?1, ?2, ?3, ?4, ?5)
+                "#,
+                params![uuid::Uuid::new_v4().to_string(), asset_id, kind, message, now],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Replace the paths an asset named that didn't resolve when it was last parsed.
+    pub fn set_unresolved_paths(&self, asset_id: &str, paths: &[String]) -> AppResult<()> {
+        let conn = self.pool.get()?;
+
+        conn.execute("DELETE FROM library_unresolved_paths WHERE asset_id = ?1", params![asset_id])?;
+        for path in paths {
+            conn.execute(
+                "INSERT OR IGNORE INTO library_unresolved_paths (asset_id, relative_path) VALUES (?1, ?2)",
+                params![asset_id, path],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Mark assets stale whose unresolved paths now name an asset with a GUID, so the
+    /// next dependency pass re-parses them. Returns the number marked.
+    pub fn mark_unresolved_path_sources_stale(&self, project_id: &str) -> AppResult<usize> {
+        let conn = self.pool.get()?;
+
+        let marked = conn.execute(
+            r#"
+            UPDATE library_assets SET deps_modified_time = NULL
+            WHERE project_id = ?1 AND id IN (
+                SELECT u.asset_id FROM library_unresolved_paths u
+                JOIN library_assets t ON t.relative_path = u.relative_path
+                WHERE t.project_id = ?1 AND t.unity_guid IS NOT NULL
+            )
+            "#,
+            params![project_id],
+        )?;
+
+        Ok(marked)
+    }
+
+    pub fn get_parse_warnings(&self, project_id: &str, kind: Option<&str>) -> AppResult<Vec<ParseWarning>> {
+        let conn = self.pool.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT w.asset_id, a.relative_path, a.file_name, w.kind, w.message, w.detected_at
+            FROM library_parse_warnings w
+            JOIN library_assets a ON a.id = w.asset_id
+            WHERE a.project_id = ?1 AND (?2 IS NULL OR w.kind = ?2)
+            ORDER BY w.kind ASC, a.relative_path ASC
+            "#,
+        )?;
+
+        let warnings: Vec<ParseWarning> = stmt
+            .query_map(params![project_id, kind], |row| {
+                Ok(ParseWarning {
+                    asset_id: row.get(0)?,
+                    relative_path: row.get(1)?,
+                    file_name: row.get(2)?,
+                    kind: row.get(3)?,
+                    message: row.get(4)?,
+                    detected_at: row.get(5)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(warnings)
+    }
+
+    /// `m_Script` references whose GUID doesn't resolve to a script in the project.
+    pub fn get_missing_script_references(&self, project_id: &str) -> AppResult<Vec<DanglingReference>> {
+        let conn = self.pool.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT d.to_guid, MIN(d.relation_type), a.id, a.file_name, a.relative_path, a.asset_type
+            FROM library_dependencies d
+            JOIN library_assets a ON a.id = d.from_asset_id
+            WHERE a.project_id = ?1 AND d.to_asset_id IS NULL AND d.to_guid IS NOT NULL
+              AND d.to_file_id = ?2
+            GROUP BY d.to_guid, a.id
+            ORDER BY a.relative_path ASC
+            "#,
+        )?;
+
+        let refs: Vec<DanglingReference> = stmt
+            .query_map(params![project_id, MONO_SCRIPT_FILE_ID], |row| {
+                Ok(DanglingReference {
+                    to_guid: row.get(0)?,
+                    relation_type: row.get(1)?,
+                    from_asset_id: row.get(2)?,
+                    from_file_name: row.get(3)?,
+                    from_relative_path: row.get(4)?,
+                    from_asset_type: row.get(5)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(refs)
+    }
+
+    /// Assets (prefabs, scenes, ScriptableObjects) whose `m_Script` points at `script_id`.
+    pub fn get_script_users(&self, script_id: &str) -> AppResult<Vec<Asset>> {
+        let conn = self.pool.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT id, project_id, absolute_path, relative_path, file_name, extension,
+                   asset_type, size_bytes, modified_time, content_hash, unity_guid,
+                   import_type, thumbnail_path, created_at, updated_at
+            FROM library_assets
+            WHERE id IN (
+                SELECT from_asset_id FROM library_dependencies
+                WHERE to_asset_id = ?1 AND to_file_id = ?2
+            )
+            ORDER BY relative_path ASC
+            "#,
+        )?;
+
+        let assets: Vec<Asset> = stmt
+            .query_map(params![script_id, MONO_SCRIPT_FILE_ID], |row| {
+                Ok(Asset {
+                    id: row.get(0)?,
+                    project_id: row.get(1)?,
+                    absolute_path: row.get(2)?,
+                    relative_path: row.get(3)?,
+                    file_name: row.get(4)?,
+                    extension: row.get(5)?,
+                    asset_type: row.get(6)?,
+                    size_bytes: row.get(7)?,
+                    modified_time: row.get(8)?,
+                    content_hash: row.get(9)?,
+                    unity_guid: row.get(10)?,
+                    import_type: row.get(11)?,
+                    thumbnail_path: row.get(12)?,
+                    created_at: row.get(13)?,
+                    updated_at: row.get(14)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(assets)
+    }
+
+    /// Dangling edges whose GUID now belongs to an asset of the project.
+    pub fn get_relinkable_dependencies(&self, project_id: &str) -> AppResult<Vec<Dependency>> {
+        let conn = self.pool.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT d.id, d.from_asset_id, d.to_asset_id, d.to_guid, d.relation_type, d.confidence, d.created_at,
+                   d.to_file_id, d.reference_type, d.sub_asset_name
+            FROM library_dependencies d
+            JOIN library_assets a ON a.id = d.from_asset_id
+            WHERE a.project_id = ?1 AND d.to_asset_id IS NULL AND d.to_guid IS NOT NULL
+              AND EXISTS (SELECT 1 FROM library_assets t WHERE t.project_id = ?1 AND t.unity_guid = d.to_guid)
+            "#,
+        )?;
+
+        let deps: Vec<Dependency> = stmt
+            .query_map(params![project_id], |row| {
+                Ok(Dependency {
+                    id: row.get(0)?,
+                    from_asset_id: row.get(1)?,
+                    to_asset_id: row.get(2)?,
+                    to_guid: row.get(3)?,
+                    relation_type: row.get(4)?,
+                    confidence: row.get(5)?,
+                    created_at: row.get(6)?,
+                    to_file_id: row.get(7)?,
+                    reference_type: row.get(8)?,
+                    sub_asset_name: row.get(9)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(deps)
+    }
+
+    /// Detach edges whose target no longer carries the GUID they were resolved from
+    /// (the target's `.meta` was regenerated). Returns the ids of assets whose edges changed.
+    pub fn detach_stale_dependencies(&self, project_id: &str) -> AppResult<Vec<String>> {
+        let conn = self.pool.get()?;
+
+        let stale_filter = r#"
+            to_asset_id IS NOT NULL
+              AND from_asset_id IN (SELECT id FROM library_assets WHERE project_id = ?1)
+              AND NOT EXISTS (
+                  SELECT 1 FROM library_assets t
+                  WHERE t.id = library_dependencies.to_asset_id
+                    AND t.unity_guid = library_dependencies.to_guid
+              )
+        "#;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT DISTINCT from_asset_id FROM library_dependencies WHERE {}",
+            stale_filter
+        ))?;
+        let sources: Vec<String> = stmt
+            .query_map(params![project_id], |row| row.get(0))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        conn.execute(
+            &format!(
+                "UPDATE library_dependencies SET to_asset_id = NULL, sub_asset_name = NULL WHERE {}",
+                stale_filter
+            ),
+            params![project_id],
+        )?;
+
+        Ok(sources)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_database() -> Database {
+        let dir = std::env::temp_dir().join(format!("library-db-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        Database::new(&dir.join("library.db")).unwrap()
+    }
+
+    #[test]
+    fn dangling_references_count_each_asset_once_per_guid() {
+        let db = temp_database();
+        let project = db.get_or_create_project("/project", "project").unwrap();
+        let asset = Asset {
+            id: uuid::Uuid::new_v4().to_string(),
+            project_id: project.id.clone(),
+            absolute_path: "/project/Assets/Hero.prefab".to_string(),
+            relative_path: "Assets/Hero.prefab".to_string(),
+            file_name: "Hero.prefab".to_string(),
+            extension: "prefab".to_string(),
+            asset_type: "prefab".to_string(),
+            size_bytes: 1,
+            modified_time: 1,
+            content_hash: None,
+            unity_guid: None,
+            import_type: None,
+            thumbnail_path: None,
+            created_at: 0,
+            updated_at: 0,
+        };
+        db.upsert_asset(&asset).unwrap();
+
+        let missing_guid = "0123456789abcdef0123456789abcdef";
+        for file_id in [21300000, -4300000] {
+            db.insert_dependency(&Dependency {
+                id: uuid::Uuid::new_v4().to_string(),
+                from_asset_id: asset.id.clone(),
+                to_asset_id: None,
+                to_guid: missing_guid.to_string(),
+                relation_type: "reference".to_string(),
+                confidence: "high".to_string(),
+                created_at: 0,
+                to_file_id: Some(file_id),
+                reference_type: Some(3),
+                sub_asset_name: None,
+            })
+            .unwrap();
+        }
+
+        let dangling = db.get_dangling_references(&project.id).unwrap();
+        assert_eq!(dangling.len(), 1);
+        assert_eq!(dangling[0].to_guid, missing_guid);
+        assert_eq!(dangling[0].from_asset_id, asset.id);
+    }
 }
@@ -1,90 +1,654 @@
+use crate::library::binary::{has_yaml_header, read_serialized_file_info};
 use crate::library::db::{Asset, Database, Dependency};
-use crate::library::error::AppResult;
+use crate::library::error::{AppError, AppResult};
+use crate::library::graph::{Direction, GraphCache};
+use crate::library::heuristics::{HeuristicMatch, HeuristicResolver, HEURISTIC_EXTENSIONS};
+use crate::library::packages::package_cache_folder;
+use crate::library::stream::read_text_chunks;
+use parking_lot::Mutex;
+use rayon::prelude::*;
 use regex::Regex;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::path::{Component, Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
+
+/// Extensions of Unity text-serialized (YAML) assets parsed for references.
+pub const YAML_EXTENSIONS: &[&str] = &[
+    "mat",
+    "prefab",
+    "unity",
+    "asset",
+    "controller",
+    "overridecontroller",
+    "anim",
+    "playable",
+    "mask",
+    "signal",
+    "spriteatlas",
+    "spriteatlasv2",
+    "mixer",
+    "terrainlayer",
+    "lighting",
+    "preset",
+    "physicmaterial",
+    "physicsmaterial2d",
+    "rendertexture",
+    "cubemap",
+    "flare",
+    "guiskin",
+    "fontsettings",
+    "brush",
+    "giparams",
+];
+
+/// Shader Graph files are JSON, with references embedded in escaped strings.
+pub const JSON_EXTENSIONS: &[&str] = &["shadergraph", "shadersubgraph"];
+
+/// Shader sources whose `#include` directives become dependency edges.
+pub const SHADER_SOURCE_EXTENSIONS: &[&str] = &["shader", "compute", "hlsl", "cginc"];
+
+/// Edge confidence levels, weakest first. Parsed GUID references are `high`; string
+/// heuristics produce `medium` and `low` edges.
+pub const CONFIDENCE_LEVELS: &[&str] = &["low", "medium", "high"];
+pub const CONFIDENCE_HIGH: &str = "high";
+/// Loading by a literal path or key: the target is almost certainly meant, but the
+/// call may be dead code or the string built differently at runtime.
+pub const CONFIDENCE_MEDIUM: &str = "medium";
+/// Something that merely looks like a reference.
+pub const CONFIDENCE_LOW: &str = "low";
+
+/// Whether an edge of `confidence` passes a `min_confidence` filter. Unknown levels
+/// rank lowest.
+pub fn meets_confidence(confidence: &str, min_confidence: &str) -> bool {
+    let rank = |level: &str| CONFIDENCE_LEVELS.iter().position(|l| *l == level);
+    rank(confidence) >= rank(min_confidence)
+}
+
+pub fn is_parseable_extension(extension: &str) -> bool {
+    let ext = extension.to_lowercase();
+    YAML_EXTENSIONS.contains(&ext.as_str())
+        || JSON_EXTENSIONS.contains(&ext.as_str())
+        || SHADER_SOURCE_EXTENSIONS.contains(&ext.as_str())
+        || HEURISTIC_EXTENSIONS.contains(&ext.as_str())
+}
+
+/// SQL list of every parseable extension, for `LOWER(extension) IN (...)` filters.
+pub fn parseable_extensions_sql() -> String {
+    YAML_EXTENSIONS
+        .iter()
+        .chain(JSON_EXTENSIONS.iter())
+        .chain(SHADER_SOURCE_EXTENSIONS.iter())
+        .chain(HEURISTIC_EXTENSIONS.iter())
+        .map(|e| format!("'{}'", e))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// GUIDs of Unity's built-in resource files. References to them never resolve to
+/// a project asset but aren't broken either.
+pub const BUILTIN_GUIDS: &[&str] = &[
+    // Library/unity default resources
+    "0000000000000000e000000000000000",
+    // Resources/unity_builtin_extra
+    "0000000000000000f000000000000000",
+    // Library/unity editor resources
+    "0000000000000000d000000000000000",
+    // Null reference written by some serializers
+    "00000000000000000000000000000000",
+];
+
+pub fn is_builtin_guid(guid: &str) -> bool {
+    BUILTIN_GUIDS.contains(&guid)
+}
+
+/// `fileID` of the MonoScript object inside a `.cs` asset, as referenced by
+/// `m_Script` on MonoBehaviour components and ScriptableObjects.
+pub const MONO_SCRIPT_FILE_ID: i64 = 11500000;
+
+/// One `{fileID: …, guid: …, type: …}` reference found in a serialized asset.
+/// Bare `guid:` mentions outside such a mapping have no `file_id`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AssetReference {
+    pub guid: String,
+    pub file_id: Option<i64>,
+    pub reference_type: Option<i32>,
+}
+
+/// References gathered across the chunks of a streamed file. Bare GUIDs only become
+/// whole-file references once the whole file is known not to reference them properly.
+#[derive(Debug, Default)]
+struct ReferenceSet {
+    references: HashSet<AssetReference>,
+    referenced_guids: HashSet<String>,
+    bare_guids: HashSet<String>,
+}
+
+impl ReferenceSet {
+    fn finish(mut self) -> Vec<AssetReference> {
+        for guid in self.bare_guids {
+            if !self.referenced_guids.contains(&guid) {
+                self.references.insert(AssetReference {
+                    guid,
+                    file_id: None,
+                    reference_type: None,
+                });
+            }
+        }
+        self.references.into_iter().collect()
+    }
+}
+
+/// A project's indexed assets by GUID and by relative path, loaded once per pass so
+/// parallel parses resolve references without going through the connection pool.
+#[derive(Debug, Default)]
+pub struct ProjectAssets {
+    root_path: Option<PathBuf>,
+    by_guid: HashMap<String, Asset>,
+    by_path: HashMap<String, Asset>,
+}
+
+impl ProjectAssets {
+    pub fn load(db: &Database, project_id: &str) -> AppResult<Self> {
+        let root_path = db.get_project(project_id)?.map(|p| PathBuf::from(p.root_path));
+        Ok(Self::new(root_path, db.get_all_assets(project_id)?))
+    }
+
+    pub fn new(root_path: Option<PathBuf>, assets: Vec<Asset>) -> Self {
+        let mut by_guid = HashMap::new();
+        let mut by_path = HashMap::new();
+        for asset in assets {
+            if let Some(guid) = asset.unity_guid.clone() {
+                by_guid.entry(guid).or_insert_with(|| asset.clone());
+            }
+            by_path.insert(asset.relative_path.clone(), asset);
+        }
+
+        Self {
+            root_path,
+            by_guid,
+            by_path,
+        }
+    }
+
+    pub fn root_path(&self) -> Option<&Path> {
+        self.root_path.as_deref()
+    }
+
+    pub fn by_guid(&self, guid: &str) -> Option<&Asset> {
+        self.by_guid.get(guid)
+    }
+
+    /// The asset at a project-relative path, after resolving `.` and `..`.
+    pub fn by_path(&self, path: &Path) -> Option<&Asset> {
+        self.by_path.get(&path_key(path)?)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Asset> {
+        self.by_path.values()
+    }
+}
+
+/// A project-relative path as stored in `relative_path`, or `None` if it leaves the project.
+pub fn path_key(path: &Path) -> Option<String> {
+    normalize_relative_path(path).map(|p| p.to_string_lossy().into_owned())
+}
+
+/// Text formats that are read whole (Shader Graph JSON, shader sources) are skipped
+/// above this size rather than loaded into memory.
+const MAX_WHOLE_FILE_BYTES: u64 = 32 * 1024 * 1024;
+
+/// Default wall-clock budget for parsing one file.
+pub const DEFAULT_PARSE_TIME_BUDGET: Duration = Duration::from_secs(30);
+
+/// Outgoing edges of one asset plus `(kind, message)` warnings about how it was parsed.
+#[derive(Debug, Default)]
+pub struct ParsedAsset {
+    pub dependencies: Vec<Dependency>,
+    pub warnings: Vec<(String, String)>,
+    /// Project-relative paths named by the file that matched no asset with a GUID
+    pub unresolved_paths: Vec<String>,
+}
 
 pub struct DependencyResolver {
     db: Arc<Database>,
+    graphs: Arc<GraphCache>,
     guid_regex: Regex,
+    reference_regex: Regex,
+    json_reference_regex: Regex,
+    function_source_regex: Regex,
+    include_regex: Regex,
+    heuristics: HeuristicResolver,
+    /// Streamed files stop being read after this long, keeping what was found so far
+    time_budget: Duration,
+    /// Project-relative folders searched for `#include` targets after the including file's folder
+    include_roots: Vec<String>,
+    /// Sub-asset name tables parsed from target `.meta` files, keyed by asset id
+    sub_asset_names: Mutex<HashMap<String, Arc<HashMap<i64, String>>>>,
+    /// `Library/PackageCache` folders of packages named by includes, keyed by project id and package
+    package_folders: Mutex<HashMap<(String, String), Option<String>>>,
 }
 
 impl DependencyResolver {
-    pub fn new(db: Arc<Database>) -> Self {
+    pub fn new(db: Arc<Database>, graphs: Arc<GraphCache>) -> Self {
         Self {
+            heuristics: HeuristicResolver::new(),
             db,
+            graphs,
             guid_regex: Regex::new(r"guid:\s*([a-f0-9]{32})").unwrap(),
+            reference_regex: Regex::new(
+                r"\{\s*fileID:\s*(-?\d+)\s*,\s*guid:\s*([a-f0-9]{32})\s*(?:,\s*type:\s*(\d+))?",
+            )
+            .unwrap(),
+            json_reference_regex: Regex::new(&json_reference_pattern()).unwrap(),
+            function_source_regex: Regex::new(
+                r#"\\?"m_FunctionSource\\?"(?:\s|\\[nrt])*:(?:\s|\\[nrt])*\\?"([a-f0-9]{32})\\?""#,
+            )
+            .unwrap(),
+            include_regex: Regex::new(r#"(?m)^[ \t]*#[ \t]*include(?:_with_pragmas)?[ \t]+"([^"]+)""#).unwrap(),
+            include_roots: Vec::new(),
+            time_budget: DEFAULT_PARSE_TIME_BUDGET,
+            sub_asset_names: Mutex::new(HashMap::new()),
+            package_folders: Mutex::new(HashMap::new()),
         }
     }
 
-    pub fn resolve_dependencies_for_asset(&self, asset: &Asset) -> AppResult<Vec<Dependency>> {
-        // Only parse certain asset types
-        match asset.asset_type.as_str() {
-            "material" | "prefab" | "scene" | "scriptable_object" => {}
-            _ => return Ok(Vec::new()),
+    /// Extra project-relative folders to search for shader includes. Includes are
+    /// always tried relative to the including file first, then to the project root.
+    pub fn with_include_roots(mut self, include_roots: Vec<String>) -> Self {
+        self.include_roots = include_roots;
+        self
+    }
+
+    pub fn with_time_budget(mut self, time_budget: Duration) -> Self {
+        self.time_budget = time_budget;
+        self
+    }
+
+    /// Parse one asset. References are resolved against `targets`, the project's
+    /// assets, so parallel parses don't each query the database per reference.
+    pub fn resolve_dependencies_for_asset(&self, asset: &Asset, targets: &ProjectAssets) -> AppResult<ParsedAsset> {
+        let mut parsed = ParsedAsset::default();
+
+        // Only parse Unity-serialized formats
+        if !is_parseable_extension(&asset.extension) {
+            return Ok(parsed);
         }
 
-        // Read file content
-        let content = match fs::read_to_string(&asset.absolute_path) {
-            Ok(c) => c,
-            Err(_) => return Ok(Vec::new()),
-        };
+        let extension = asset.extension.to_lowercase();
+        let path = Path::new(&asset.absolute_path);
+
+        // Force Binary / Mixed serialization: only the externals table is readable
+        if YAML_EXTENSIONS.contains(&extension.as_str()) && !has_yaml_header(path) {
+            if let Some(info) = read_serialized_file_info(path) {
+                let references: HashSet<AssetReference> = info
+                    .externals
+                    .iter()
+                    .filter(|external| external.guid.chars().any(|c| c != '0'))
+                    .map(|external| AssetReference {
+                        guid: external.guid.clone(),
+                        file_id: None,
+                        reference_type: None,
+                    })
+                    .collect();
+
+                parsed.warnings.push((
+                    "binary_serialized".to_string(),
+                    format!(
+                        "Binary serialized file (format {}, Unity {}); read {} external file \
+                         reference(s), per-object references are not available",
+                        info.format_version,
+                        info.unity_version,
+                        info.externals.len()
+                    ),
+                ));
+                parsed.dependencies = self.dependencies_from_references(asset, references, targets);
+                return Ok(parsed);
+            }
+        }
+
+        // Shader Graph JSON and shader sources are small and parsed as a whole
+        if JSON_EXTENSIONS.contains(&extension.as_str()) || SHADER_SOURCE_EXTENSIONS.contains(&extension.as_str()) {
+            let content = match read_whole_text(path, &mut parsed.warnings) {
+                Some(c) => c,
+                None => return Ok(parsed),
+            };
+
+            parsed.dependencies = if SHADER_SOURCE_EXTENSIONS.contains(&extension.as_str()) {
+                self.resolve_shader_includes(asset, &content, targets, &mut parsed.unresolved_paths)
+            } else {
+                let references = self.extract_json_references(&content);
+                self.dependencies_from_references(asset, references, targets)
+            };
+            return Ok(parsed);
+        }
+
+        // Scenes and other YAML can be hundreds of MB, so they are streamed
+        let heuristic = HEURISTIC_EXTENSIONS.contains(&extension.as_str());
+        let mut references = ReferenceSet::default();
+        let mut matches = Vec::new();
+
+        let outcome = read_text_chunks(path, self.time_budget, |chunk| {
+            if heuristic {
+                matches.extend(
+                    self.heuristics
+                        .find_references(asset, chunk, targets, &mut parsed.unresolved_paths),
+                );
+            } else {
+                self.collect_references(chunk, &mut references);
+            }
+            Ok(())
+        });
+
+        match outcome {
+            Ok(outcome) if outcome.timed_out => {
+                parsed.warnings.push((
+                    "time_budget".to_string(),
+                    format!(
+                        "Parsing stopped after {:.1}s at {} of {} bytes; dependencies found past that point are missing",
+                        outcome.elapsed.as_secs_f64(),
+                        outcome.bytes_read,
+                        outcome.total_bytes
+                    ),
+                ));
+            }
+            Ok(outcome) if outcome.invalid_utf8 => {
+                parsed.warnings.push((
+                    "unreadable".to_string(),
+                    "File is not valid UTF-8 text; only its readable parts were searched".to_string(),
+                ));
+            }
+            Ok(_) => {}
+            Err(AppError::Io(e)) => {
+                parsed
+                    .warnings
+                    .push(("unreadable".to_string(), format!("Could not read file as text: {}", e)));
+                return Ok(parsed);
+            }
+            Err(e) => return Err(e),
+        }
 
-        // Extract GUIDs from content
-        let guids = self.extract_guids(&content);
+        parsed.dependencies = if heuristic {
+            self.resolve_heuristic_references(asset, matches)
+        } else {
+            self.dependencies_from_references(asset, references.finish(), targets)
+        };
+        Ok(parsed)
+    }
 
+    fn dependencies_from_references(
+        &self,
+        asset: &Asset,
+        references: impl IntoIterator<Item = AssetReference>,
+        targets: &ProjectAssets,
+    ) -> Vec<Dependency> {
         // Filter out self-reference and create dependencies
         let mut dependencies = Vec::new();
         let now = chrono::Utc::now().timestamp();
 
-        for guid in guids {
+        for reference in references {
             // Skip self-reference
-            if asset.unity_guid.as_ref() == Some(&guid) {
+            if asset.unity_guid.as_deref() == Some(reference.guid.as_str()) {
                 continue;
             }
 
             // Try to resolve the GUID to an asset
-            let to_asset = self.db.get_asset_by_guid(&asset.project_id, &guid)?;
+            let to_asset = targets.by_guid(&reference.guid).cloned();
+
+            let relation_type = self.infer_relation_type(asset, &to_asset, reference.file_id);
 
-            let relation_type = self.infer_relation_type(&asset.asset_type, &to_asset);
+            let sub_asset_name = match (&to_asset, reference.file_id) {
+                (Some(target), Some(file_id)) => self.sub_asset_name(target, file_id),
+                _ => None,
+            };
 
             let dep = Dependency {
                 id: uuid::Uuid::new_v4().to_string(),
                 from_asset_id: asset.id.clone(),
                 to_asset_id: to_asset.map(|a| a.id),
-                to_guid: guid,
+                to_guid: reference.guid,
                 relation_type,
-                confidence: "high".to_string(),
+                confidence: CONFIDENCE_HIGH.to_string(),
                 created_at: now,
+                to_file_id: reference.file_id,
+                reference_type: reference.reference_type,
+                sub_asset_name,
             };
 
             dependencies.push(dep);
         }
 
-        Ok(dependencies)
+        dependencies
     }
 
-    fn extract_guids(&self, content: &str) -> Vec<String> {
-        let mut guids = HashSet::new();
+    /// Collect distinct `(guid, fileID, type)` references from one chunk of a file.
+    fn collect_references(&self, content: &str, set: &mut ReferenceSet) {
+        for cap in self.reference_regex.captures_iter(content) {
+            let guid = cap[2].to_string();
+            set.referenced_guids.insert(guid.clone());
+            set.references.insert(AssetReference {
+                guid,
+                file_id: cap[1].parse().ok(),
+                reference_type: cap.get(3).and_then(|m| m.as_str().parse().ok()),
+            });
+        }
 
         for cap in self.guid_regex.captures_iter(content) {
-            if let Some(m) = cap.get(1) {
-                guids.insert(m.as_str().to_string());
+            set.bare_guids.insert(cap[1].to_string());
+        }
+    }
+
+    /// Collect references from Shader Graph JSON (textures, sub-graphs, custom function files).
+    pub fn extract_json_references(&self, content: &str) -> Vec<AssetReference> {
+        let mut references = HashSet::new();
+
+        for cap in self.json_reference_regex.captures_iter(content) {
+            references.insert(AssetReference {
+                guid: cap[2].to_string(),
+                file_id: cap[1].parse().ok(),
+                reference_type: cap.get(3).and_then(|m| m.as_str().parse().ok()),
+            });
+        }
+
+        // Custom Function nodes point at their .hlsl file by bare GUID
+        for cap in self.function_source_regex.captures_iter(content) {
+            references.insert(AssetReference {
+                guid: cap[1].to_string(),
+                file_id: None,
+                reference_type: None,
+            });
+        }
+
+        references.into_iter().collect()
+    }
+
+    /// Collect the distinct paths named by `#include` and `#include_with_pragmas`.
+    pub fn extract_includes(&self, content: &str) -> Vec<String> {
+        let mut seen = HashSet::new();
+        self.include_regex
+            .captures_iter(content)
+            .map(|cap| cap[1].trim().to_string())
+            .filter(|path| seen.insert(path.clone()))
+            .collect()
+    }
+
+    /// Turn each `#include` into an edge to the indexed file it names. Includes that
+    /// resolve outside the index (Unity's built-in CGIncludes, uninstalled packages)
+    /// or to a file without a GUID are skipped.
+    fn resolve_shader_includes(
+        &self,
+        asset: &Asset,
+        content: &str,
+        targets: &ProjectAssets,
+        unresolved_paths: &mut Vec<String>,
+    ) -> Vec<Dependency> {
+        let mut dependencies = Vec::new();
+        let now = chrono::Utc::now().timestamp();
+
+        for include in self.extract_includes(content) {
+            let candidates = self.include_candidates(asset, &include, targets);
+            let target = match candidates.iter().find_map(|c| targets.by_path(Path::new(c))) {
+                Some(t) if t.id != asset.id => t.clone(),
+                Some(_) => continue,
+                None => {
+                    tracing::debug!("Unresolved include \"{}\" in {}", include, asset.relative_path);
+                    unresolved_paths.extend(candidates);
+                    continue;
+                }
+            };
+            let to_guid = match target.unity_guid.clone() {
+                Some(g) => g,
+                None => {
+                    unresolved_paths.push(target.relative_path);
+                    continue;
+                }
+            };
+            let to_asset = Some(target);
+
+            dependencies.push(Dependency {
+                id: uuid::Uuid::new_v4().to_string(),
+                from_asset_id: asset.id.clone(),
+                relation_type: self.infer_relation_type(asset, &to_asset, None),
+                to_asset_id: to_asset.map(|a| a.id),
+                to_guid,
+                confidence: CONFIDENCE_HIGH.to_string(),
+                created_at: now,
+                to_file_id: None,
+                reference_type: None,
+                sub_asset_name: None,
+            });
+        }
+
+        dependencies
+    }
+
+    /// Edges for assets named by string rather than GUID. Only targets with a GUID are
+    /// kept, so the edges survive moves and relinking like parsed ones.
+    fn resolve_heuristic_references(&self, asset: &Asset, matches: Vec<HeuristicMatch>) -> Vec<Dependency> {
+        let now = chrono::Utc::now().timestamp();
+        let mut seen = HashSet::new();
+
+        matches
+            .into_iter()
+            .filter(|m| seen.insert((m.target.id.clone(), m.relation_type)))
+            .filter_map(|m| {
+                let to_guid = m.target.unity_guid.clone()?;
+                Some(Dependency {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    from_asset_id: asset.id.clone(),
+                    to_asset_id: Some(m.target.id),
+                    to_guid,
+                    relation_type: m.relation_type.to_string(),
+                    confidence: m.confidence.to_string(),
+                    created_at: now,
+                    to_file_id: None,
+                    reference_type: None,
+                    sub_asset_name: None,
+                })
+            })
+            .collect()
+    }
+
+    /// Project-relative paths an include may name, in the order they are tried.
+    fn include_candidates(&self, asset: &Asset, include: &str, targets: &ProjectAssets) -> Vec<String> {
+        let including_dir = Path::new(&asset.relative_path)
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+
+        let mut candidates = vec![including_dir.join(include), PathBuf::from(include)];
+        candidates.extend(self.include_roots.iter().map(|root| Path::new(root).join(include)));
+
+        // `Packages/<name>/...` also names registry and git packages, which live in the cache
+        if let Some((name, rest)) = include.strip_prefix("Packages/").and_then(|p| p.split_once('/')) {
+            if let Some(folder) = self.package_folder(&asset.project_id, name, targets) {
+                candidates.push(Path::new(&folder).join(rest));
             }
         }
 
-        guids.into_iter().collect()
+        candidates.iter().filter_map(|candidate| path_key(candidate)).collect()
+    }
+
+    fn package_folder(&self, project_id: &str, name: &str, targets: &ProjectAssets) -> Option<String> {
+        let key = (project_id.to_string(), name.to_string());
+        if let Some(folder) = self.package_folders.lock().get(&key) {
+            return folder.clone();
+        }
+
+        let folder = targets
+            .root_path()
+            .and_then(|root| package_cache_folder(root, name));
+        self.package_folders.lock().insert(key, folder.clone());
+        folder
+    }
+
+    fn sub_asset_name(&self, target: &Asset, file_id: i64) -> Option<String> {
+        let table = {
+            let mut cache = self.sub_asset_names.lock();
+            Arc::clone(cache.entry(target.id.clone()).or_insert_with(|| {
+                let meta_path = format!("{}.meta", target.absolute_path);
+                let names = fs::read_to_string(Path::new(&meta_path))
+                    .map(|content| parse_sub_asset_names(&content))
+                    .unwrap_or_default();
+                Arc::new(names)
+            }))
+        };
+
+        table.get(&file_id).cloned()
     }
 
-    fn infer_relation_type(&self, from_type: &str, to_asset: &Option<Asset>) -> String {
+    fn infer_relation_type(&self, from: &Asset, to_asset: &Option<Asset>, to_file_id: Option<i64>) -> String {
+        // m_Script points at the MonoScript whether or not the script still exists
+        if to_file_id == Some(MONO_SCRIPT_FILE_ID) {
+            return "script_usage".to_string();
+        }
+
         let to_type = to_asset
             .as_ref()
             .map(|a| a.asset_type.as_str())
             .unwrap_or("unknown");
+        let from_ext = from.extension.to_lowercase();
+        let to_ext = to_asset
+            .as_ref()
+            .map(|a| a.extension.to_lowercase())
+            .unwrap_or_default();
+
+        // Unity-native formats that share a coarse asset_type are told apart by extension
+        let by_extension = match (from_ext.as_str(), to_type, to_ext.as_str()) {
+            ("controller", _, "anim") => Some("controller_clip"),
+            ("controller", _, _) => Some("controller_reference"),
+            ("overridecontroller", _, "controller") => Some("override_base_controller"),
+            ("overridecontroller", _, "anim") => Some("override_clip"),
+            ("overridecontroller", _, _) => Some("override_reference"),
+            ("anim", "texture", _) => Some("animation_sprite"),
+            ("anim", _, _) => Some("animation_reference"),
+            ("playable", _, "anim") => Some("timeline_clip"),
+            ("playable", "audio", _) => Some("timeline_audio"),
+            ("playable", "prefab", _) => Some("timeline_prefab"),
+            ("playable", _, _) => Some("timeline_reference"),
+            ("spriteatlas" | "spriteatlasv2", "texture", _) => Some("atlas_sprite"),
+            ("spriteatlas" | "spriteatlasv2", _, _) => Some("atlas_reference"),
+            ("mixer", _, "mixer") => Some("mixer_mixer"),
+            ("mixer", _, _) => Some("mixer_reference"),
+            ("terrainlayer", "texture", _) => Some("terrainlayer_texture"),
+            ("terrainlayer", _, _) => Some("terrainlayer_reference"),
+            ("lighting", _, _) => Some("lighting_reference"),
+            ("preset", _, _) => Some("preset_reference"),
+            ("shader" | "compute" | "hlsl" | "cginc", _, _) => Some("shader_include"),
+            ("shadergraph" | "shadersubgraph", _, "hlsl" | "cginc") => Some("shadergraph_function"),
+            ("shadergraph" | "shadersubgraph", _, "shadersubgraph") => Some("shadergraph_subgraph"),
+            ("shadergraph" | "shadersubgraph", "texture", _) => Some("shadergraph_texture"),
+            ("shadergraph" | "shadersubgraph", _, _) => Some("shadergraph_reference"),
+            _ => None,
+        };
+
+        if let Some(relation) = by_extension {
+            return relation.to_string();
+        }
 
-        match (from_type, to_type) {
+        match (from.asset_type.as_str(), to_type) {
             ("material", "texture") => "material_texture".to_string(),
             ("material", "shader") => "material_shader".to_string(),
             ("prefab", "material") => "prefab_material".to_string(),
@@ -102,77 +666,325 @@ impl DependencyResolver {
         &self,
         project_id: &str,
         cancel_flag: Arc<AtomicBool>,
-        mut progress_callback: impl FnMut(usize, usize),
+        progress_callback: impl FnMut(usize, usize),
     ) -> AppResult<usize> {
         let assets = self.db.get_parseable_assets(project_id)?;
+
+        // Nearly every edge changes, so reload the cached graph rather than patch it
+        self.graphs.invalidate(project_id);
+
+        let (total_deps, _) =
+            self.replace_dependencies_parallel(project_id, assets, &cancel_flag, progress_callback)?;
+
+        self.graphs.invalidate(project_id);
+
+        Ok(total_deps)
+    }
+
+    /// Re-resolve only assets that are new or changed since their dependencies were
+    /// last parsed, or that named a path which has since been indexed, then re-link
+    /// dangling edges whose targets may have just appeared.
+    /// Assets interrupted by a cancelled scan stay stale and are picked up next time.
+    pub fn resolve_changed_with_progress(
+        &self,
+        project_id: &str,
+        cancel_flag: Arc<AtomicBool>,
+        progress_callback: impl FnMut(usize, usize),
+    ) -> AppResult<usize> {
+        // Files named by path before they existed are re-parsed now that they do
+        self.db.mark_unresolved_path_sources_stale(project_id)?;
+        let assets = self.db.get_assets_with_stale_dependencies(project_id)?;
+
+        let (total_deps, resolved) =
+            self.replace_dependencies_parallel(project_id, assets, &cancel_flag, progress_callback)?;
+        self.graphs.refresh_assets(&self.db, project_id, &resolved)?;
+
+        if cancel_flag.load(Ordering::SeqCst) {
+            return Ok(total_deps);
+        }
+
+        let relinked = self.relink_dangling(project_id)?;
+        if relinked > 0 {
+            tracing::info!("Re-linked {} dangling dependencies", relinked);
+        }
+
+        Ok(total_deps)
+    }
+
+    /// Parse `assets` across cores and store the results from this thread, a batch at
+    /// a time so cancellation is noticed promptly. Returns the number of edges stored
+    /// and the ids of the assets that were re-parsed.
+    fn replace_dependencies_parallel(
+        &self,
+        project_id: &str,
+        assets: Vec<Asset>,
+        cancel_flag: &AtomicBool,
+        mut progress_callback: impl FnMut(usize, usize),
+    ) -> AppResult<(usize, HashSet<String>)> {
         let total = assets.len();
+        let batch_size = rayon::current_num_threads() * 4;
         let mut total_deps = 0;
+        let mut resolved = HashSet::new();
+        if assets.is_empty() {
+            return Ok((total_deps, resolved));
+        }
+
+        // Loaded once up front: the pool has far fewer connections than rayon threads
+        let targets = ProjectAssets::load(&self.db, project_id)?;
         let mut processed = 0;
 
-        for asset in assets {
-            // Check cancellation
+        for batch in assets.chunks(batch_size) {
             if cancel_flag.load(Ordering::SeqCst) {
-                return Ok(total_deps);
+                break;
+            }
+
+            let parsed: Vec<AppResult<ParsedAsset>> = batch
+                .par_iter()
+                .map(|asset| self.resolve_dependencies_for_asset(asset, &targets))
+                .collect();
+
+            for (asset, parsed) in batch.iter().zip(parsed) {
+                match parsed {
+                    Ok(parsed) => {
+                        total_deps += self.store_dependencies(asset, &parsed)?;
+                        resolved.insert(asset.id.clone());
+                    }
+                    // Keep the old edges and leave the asset stale so the next pass retries it
+                    Err(e) => {
+                        tracing::warn!("Failed to parse {}: {}", asset.relative_path, e);
+                        self.db.set_parse_warnings(
+                            &asset.id,
+                            &[("unreadable".to_string(), format!("Could not parse file: {}", e))],
+                        )?;
+                    }
+                }
+                processed += 1;
+                progress_callback(processed, total);
             }
+        }
+
+        Ok((total_deps, resolved))
+    }
 
-            // Clear existing dependencies for this asset
-            self.db.delete_dependencies_for_asset(&asset.id)?;
+    /// Replace the outgoing dependencies and parse warnings of one asset.
+    fn store_dependencies(&self, asset: &Asset, parsed: &ParsedAsset) -> AppResult<usize> {
+        self.db.delete_dependencies_for_asset(&asset.id)?;
 
-            // Resolve new dependencies
-            let deps = self.resolve_dependencies_for_asset(&asset)?;
+        for dep in &parsed.dependencies {
+            self.db.insert_dependency(dep)?;
+        }
+        self.db.set_parse_warnings(&asset.id, &parsed.warnings)?;
+        self.db.set_unresolved_paths(&asset.id, &parsed.unresolved_paths)?;
 
-            for dep in &deps {
-                self.db.insert_dependency(dep)?;
-            }
+        self.db.mark_dependencies_resolved(&asset.id, asset.modified_time)?;
+
+        Ok(parsed.dependencies.len())
+    }
+
+    /// Point edges at the asset currently carrying their `to_guid`. Edges whose target
+    /// changed GUID are detached first, then every dangling edge whose GUID now
+    /// resolves is linked and gets its relation type re-inferred.
+    pub fn relink_dangling(&self, project_id: &str) -> AppResult<usize> {
+        let mut changed: HashSet<String> = self.db.detach_stale_dependencies(project_id)?.into_iter().collect();
+
+        let mut relinked = 0;
+        let mut sources: HashMap<String, Asset> = HashMap::new();
+
+        for mut dep in self.db.get_relinkable_dependencies(project_id)? {
+            let to_asset = match self.db.get_asset_by_guid(project_id, &dep.to_guid)? {
+                Some(a) => a,
+                None => continue,
+            };
 
-            total_deps += deps.len();
-            processed += 1;
+            if !sources.contains_key(&dep.from_asset_id) {
+                match self.db.get_asset(&dep.from_asset_id)? {
+                    Some(from) => {
+                        sources.insert(dep.from_asset_id.clone(), from);
+                    }
+                    None => continue,
+                }
+            }
+            let from = &sources[&dep.from_asset_id];
 
-            // Report progress
-            progress_callback(processed, total);
+            dep.sub_asset_name = dep.to_file_id.and_then(|file_id| self.sub_asset_name(&to_asset, file_id));
+            dep.to_asset_id = Some(to_asset.id.clone());
+            // Heuristic edges keep the relation that matched them
+            if dep.confidence == CONFIDENCE_HIGH {
+                dep.relation_type = self.infer_relation_type(from, &Some(to_asset), dep.to_file_id);
+            }
+            self.db.insert_dependency(&dep)?;
+            changed.insert(dep.from_asset_id);
+            relinked += 1;
         }
 
-        Ok(total_deps)
+        self.graphs.refresh_assets(&self.db, project_id, &changed)?;
+
+        Ok(relinked)
     }
 
+    /// Ids of everything `asset_id` depends on within `max_depth` hops, nearest first,
+    /// following only edges of at least `min_confidence`.
     pub fn get_dependency_tree(
         &self,
         asset_id: &str,
         max_depth: usize,
+        min_confidence: &str,
     ) -> AppResult<Vec<String>> {
-        let mut visited = HashSet::new();
-        let mut result = Vec::new();
+        let asset = match self.db.get_asset(asset_id)? {
+            Some(a) => a,
+            None => return Ok(Vec::new()),
+        };
 
-        self.collect_dependencies(asset_id, 0, max_depth, &mut visited, &mut result)?;
+        let graph = self.graphs.get(&self.db, &asset.project_id)?;
 
-        Ok(result)
+        Ok(graph
+            .walk(asset_id, Direction::Dependencies, Some(max_depth), |e| {
+                meets_confidence(&e.confidence, min_confidence)
+            })
+            .into_iter()
+            .map(|r| r.asset_id)
+            .collect())
     }
+}
 
-    fn collect_dependencies(
-        &self,
-        asset_id: &str,
-        depth: usize,
-        max_depth: usize,
-        visited: &mut HashSet<String>,
-        result: &mut Vec<String>,
-    ) -> AppResult<()> {
-        if depth >= max_depth || visited.contains(asset_id) {
-            return Ok(());
-        }
+/// `{"fileID": …, "guid": "…", "type": …}` as it appears in Shader Graph JSON, where the
+/// object is often itself a JSON string: quotes may be escaped and whitespace may be a
+/// literal `\n`, `\r` or `\t`.
+fn json_reference_pattern() -> String {
+    let quote = r#"\\?""#;
+    let sep = r"(?:\s|\\[nrt])*";
+    format!(
+        r#"{q}fileID{q}{s}:{s}(-?\d+){s},{s}{q}guid{q}{s}:{s}{q}([a-f0-9]{{32}}){q}(?:{s},{s}{q}type{q}{s}:{s}(\d+))?"#,
+        q = quote,
+        s = sep
+    )
+}
 
-        visited.insert(asset_id.to_string());
+/// Read a small text file whole, recording a warning instead when it can't be read
+/// or is too large to load.
+fn read_whole_text(path: &Path, warnings: &mut Vec<(String, String)>) -> Option<String> {
+    match fs::metadata(path) {
+        Ok(meta) if meta.len() > MAX_WHOLE_FILE_BYTES => {
+            warnings.push((
+                "too_large".to_string(),
+                format!(
+                    "Skipped: {} bytes is over the {} byte limit for this format",
+                    meta.len(),
+                    MAX_WHOLE_FILE_BYTES
+                ),
+            ));
+            return None;
+        }
+        _ => {}
+    }
 
-        let deps = self.db.get_dependencies(asset_id)?;
+    match fs::read_to_string(path) {
+        Ok(content) => Some(content),
+        Err(e) => {
+            warnings.push(("unreadable".to_string(), format!("Could not read file as text: {}", e)));
+            None
+        }
+    }
+}
 
-        for dep in deps {
-            if let Some(to_id) = dep.to_asset_id {
-                if !visited.contains(&to_id) {
-                    result.push(to_id.clone());
-                    self.collect_dependencies(&to_id, depth + 1, max_depth, visited, result)?;
+/// Collapse `.` and `..` in a project-relative path, rebuilding it with the platform
+/// separator so it matches stored `relative_path`s. `None` if it escapes the project.
+pub fn normalize_relative_path(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => normalized.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return None;
                 }
             }
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(normalized)
+}
+
+#[derive(PartialEq)]
+enum MetaSection {
+    None,
+    RecycleNames,
+    InternalIdTable,
+    Sprites,
+}
+
+/// Map sub-asset `fileID`s to names using a `.meta` file's `fileIDToRecycleName`
+/// (older importers), `internalIDToNameTable` (2019.3+) and sprite sheet entries.
+pub fn parse_sub_asset_names(meta_content: &str) -> HashMap<i64, String> {
+    let mut names = HashMap::new();
+    let mut section = MetaSection::None;
+    let mut section_indent = 0;
+    let mut pending_id: Option<i64> = None;
+    let mut pending_name: Option<String> = None;
+
+    for line in meta_content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+
+        let entered = match trimmed {
+            "fileIDToRecycleName:" => Some(MetaSection::RecycleNames),
+            "internalIDToNameTable:" => Some(MetaSection::InternalIdTable),
+            "sprites:" => Some(MetaSection::Sprites),
+            _ => None,
+        };
+        if let Some(next) = entered {
+            section = next;
+            section_indent = indent;
+            pending_id = None;
+            pending_name = None;
+            continue;
+        }
+
+        // A key at or above the section's indent ends it; list items may share the indent
+        if section != MetaSection::None && indent <= section_indent && !trimmed.starts_with("- ") {
+            section = MetaSection::None;
         }
 
-        Ok(())
+        match section {
+            MetaSection::None => {}
+            MetaSection::RecycleNames => {
+                if let Some((key, value)) = trimmed.split_once(':') {
+                    if let Ok(id) = key.trim().parse::<i64>() {
+                        let name = value.trim();
+                        if !name.is_empty() {
+                            names.insert(id, name.to_string());
+                        }
+                    }
+                }
+            }
+            MetaSection::InternalIdTable => {
+                if trimmed == "- first:" {
+                    pending_id = None;
+                } else if let Some(name) = trimmed.strip_prefix("second:") {
+                    if let Some(id) = pending_id.take() {
+                        names.insert(id, name.trim().to_string());
+                    }
+                } else if let Some((_, value)) = trimmed.split_once(':') {
+                    if let Ok(id) = value.trim().parse::<i64>() {
+                        pending_id = Some(id);
+                    }
+                }
+            }
+            MetaSection::Sprites => {
+                if let Some(name) = trimmed.strip_prefix("- name:").or_else(|| trimmed.strip_prefix("name:")) {
+                    pending_name = Some(name.trim().to_string());
+                } else if let Some(value) = trimmed.strip_prefix("internalID:") {
+                    if let (Ok(id), Some(name)) = (value.trim().parse::<i64>(), pending_name.take()) {
+                        names.entry(id).or_insert(name);
+                    }
+                }
+            }
+        }
     }
+
+    names
 }
@@ -1,20 +1,61 @@
-use crate::library::db::{Asset, Database};
-use crate::library::deps::DependencyResolver;
-use crate::library::error::AppResult;
+use crate::library::binary::{has_yaml_header, read_serialized_file_info};
+use crate::library::db::{normalize_folder_path, Asset, Database};
+use crate::library::deps::{meets_confidence, CONFIDENCE_HIGH, JSON_EXTENSIONS, YAML_EXTENSIONS};
+use crate::library::error::{AppError, AppResult};
+use crate::library::graph::GraphCache;
+use crate::library::packages::{asset_scope, package_of_path, SCOPE_PACKAGE};
+use crate::library::scanner::read_unity_guid;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use regex::bytes::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
-use std::fs;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read, Write};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Name of the manifest written at the root of every bundle.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Other JSON assets whose GUID references are rewritten: assembly definitions and
+/// references name their assemblies as `"GUID:<hex>"`.
+const REWRITTEN_JSON_EXTENSIONS: &[&str] = &["asmdef", "asmref", "json"];
+
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct ExportManifest {
     pub version: String,
     pub exported_at: String,
     pub source_project: String,
+    /// First of `roots`, kept for readers of single-root bundles
     pub root_asset: String,
+    #[serde(default)]
+    pub roots: Vec<String>,
+    #[serde(default)]
+    pub max_depth: Option<usize>,
+    /// Asset types left out of the bundle, dependencies included
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub excluded_types: Vec<String>,
     pub assets: Vec<ExportedAsset>,
     pub dependency_graph: Vec<DependencyEdge>,
+    /// Original GUID to the fresh one written in its place, when GUIDs were regenerated
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub guid_map: BTreeMap<String, String>,
+    /// Binary serialized files referencing regenerated GUIDs, which couldn't be rewritten
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unrewritten_files: Vec<String>,
+    /// Package assets the bundle references but doesn't carry; the target project
+    /// needs these packages installed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub package_dependencies: Vec<PackageDependency>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PackageDependency {
+    pub package: String,
+    pub relative_path: String,
+    pub unity_guid: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -31,124 +72,708 @@ pub struct DependencyEdge {
     pub relation_type: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct ExportResult {
     pub success: bool,
     pub exported_files: Vec<String>,
     pub manifest_path: Option<String>,
     pub error: Option<String>,
+    /// Files left alone: identical at the destination, or skipped by the conflict policy
+    pub skipped_files: Vec<String>,
+    pub failures: Vec<ExportFailure>,
+    /// Binary files written with their original GUID references
+    pub unrewritten_files: Vec<String>,
+    pub plan: Option<ExportPlan>,
+}
+
+impl ExportResult {
+    /// A dry run's result: the plan, with nothing written.
+    pub fn planned(plan: ExportPlan) -> Self {
+        Self {
+            success: !plan.aborted && plan.blocked_count == 0,
+            error: if plan.aborted {
+                Some(abort_message(&plan))
+            } else {
+                (plan.blocked_count > 0).then(|| format!("{} files can't be exported", plan.blocked_count))
+            },
+            skipped_files: plan.excluded_roots.clone(),
+            plan: Some(plan),
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportFailure {
+    pub path: String,
+    pub error: String,
+}
+
+/// What to do with a destination file that exists with other content.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictPolicy {
+    Skip,
+    Overwrite,
+    /// Write next to it as `name 1.ext`, `name 2.ext`, ...
+    Rename,
+    /// Write nothing if anything conflicts
+    #[default]
+    Abort,
+}
+
+impl ConflictPolicy {
+    pub fn parse(value: &str) -> AppResult<Self> {
+        match value.to_lowercase().as_str() {
+            "skip" => Ok(Self::Skip),
+            "overwrite" => Ok(Self::Overwrite),
+            "rename" => Ok(Self::Rename),
+            "abort" => Ok(Self::Abort),
+            other => Err(AppError::Custom(format!("Unknown conflict policy: {}", other))),
+        }
+    }
+}
+
+/// How a bundle is exported, as sent by the frontend. Unset fields take the defaults.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExportOptions {
+    /// `folder` (the default) or `unitypackage`
+    pub format: Option<String>,
+    /// Dependency levels followed from the roots; 5 by default
+    pub max_depth: Option<usize>,
+    pub exclude_types: Option<Vec<String>>,
+    pub regenerate_guids: Option<bool>,
+    /// Parsed by `ConflictPolicy::parse`; `abort` by default
+    pub conflict_policy: Option<String>,
+    /// Weakest edge confidence followed; `high` by default
+    pub min_confidence: Option<String>,
+    /// Return the plan without writing anything
+    pub dry_run: Option<bool>,
+}
+
+/// One asset (with its `.meta`) an export would write.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportPlanEntry {
+    /// Path relative to the destination folder
+    pub relative_path: String,
+    /// `new`, `identical`, `differs` or `would_overwrite`, for the asset and its `.meta` together
+    pub status: String,
+    /// `write`, `skip`, `overwrite`, `rename` or `blocked`
+    pub action: String,
+    /// Where the asset will be written; a new name when renamed, `None` when skipped or blocked
+    pub write_path: Option<String>,
+    pub has_meta: bool,
+    /// Why a `blocked` entry can't be written
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportPlan {
+    pub dest_folder: String,
+    pub policy: ConflictPolicy,
+    pub entries: Vec<ExportPlanEntry>,
+    pub new_count: usize,
+    pub identical_count: usize,
+    /// Entries whose destination has other content
+    pub conflict_count: usize,
+    /// Entries the conflict policy can't write; reported as failures
+    pub blocked_count: usize,
+    /// Conflicts under the `abort` policy; nothing will be written
+    pub aborted: bool,
+    /// Roots left out because their type is excluded
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub excluded_roots: Vec<String>,
+}
+
+impl ExportPlan {
+    fn new(dest_folder: &Path, policy: ConflictPolicy, entries: Vec<ExportPlanEntry>, aborted: bool) -> Self {
+        let count = |statuses: &[&str]| entries.iter().filter(|e| statuses.contains(&e.status.as_str())).count();
+        Self {
+            dest_folder: dest_folder.to_string_lossy().to_string(),
+            policy,
+            new_count: count(&["new"]),
+            identical_count: count(&["identical"]),
+            conflict_count: count(&["differs", "would_overwrite"]),
+            blocked_count: entries.iter().filter(|e| e.action == "blocked").count(),
+            entries,
+            aborted,
+            excluded_roots: Vec::new(),
+        }
+    }
+}
+
+/// What `collect_bundle` found: the assets to copy, the package assets they
+/// reference, which stay out of the bundle, and the roots whose type is excluded.
+struct BundleContents {
+    assets: Vec<Asset>,
+    packages: Vec<PackageDependency>,
+    excluded_roots: Vec<String>,
+}
+
+/// A folder enclosing bundled assets, other than `Assets` itself, that has a `.meta`.
+struct BundleFolder {
+    relative_path: String,
+    meta_path: PathBuf,
+    guid: String,
 }
 
 pub struct Exporter {
     db: Arc<Database>,
-    dep_resolver: Arc<DependencyResolver>,
+    graphs: Arc<GraphCache>,
+    excluded_types: HashSet<String>,
+    regenerate_guids: bool,
+    policy: ConflictPolicy,
+    min_confidence: String,
 }
 
 impl Exporter {
-    pub fn new(db: Arc<Database>) -> Self {
-        let dep_resolver = Arc::new(DependencyResolver::new(Arc::clone(&db)));
-        Self { db, dep_resolver }
+    pub fn new(db: Arc<Database>, graphs: Arc<GraphCache>) -> Self {
+        Self {
+            db,
+            graphs,
+            excluded_types: HashSet::new(),
+            regenerate_guids: false,
+            policy: ConflictPolicy::default(),
+            min_confidence: CONFIDENCE_HIGH.to_string(),
+        }
     }
 
-    pub fn export_file(&self, asset: &Asset, dest_folder: &Path) -> AppResult<ExportResult> {
-        let source_path = Path::new(&asset.absolute_path);
+    /// Give every bundled asset a fresh GUID and rewrite the references between them,
+    /// so the bundle can go into a project that already holds older copies.
+    pub fn with_regenerated_guids(mut self, regenerate_guids: bool) -> Self {
+        self.regenerate_guids = regenerate_guids;
+        self
+    }
+
+    /// Leave assets of these types (`script`, `shader`, ...) out of bundles, along with
+    /// whatever only they depend on.
+    pub fn with_excluded_types(mut self, excluded_types: Vec<String>) -> Self {
+        self.excluded_types = excluded_types.into_iter().map(|t| t.to_lowercase()).collect();
+        self
+    }
+
+    /// Only follow dependency edges of at least this confidence when collecting bundles.
+    /// Defaults to parsed references, leaving out string-matched guesses.
+    pub fn with_min_confidence(mut self, min_confidence: &str) -> Self {
+        self.min_confidence = min_confidence.to_string();
+        self
+    }
+
+    /// What to do when a destination file already exists with other content.
+    pub fn with_conflict_policy(mut self, policy: ConflictPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// List what `export_file` would write, without writing anything.
+    pub fn plan_file(&self, asset: &Asset, dest_folder: &Path) -> AppResult<ExportPlan> {
+        let assets: Vec<Asset> = Some(asset.clone())
+            .filter(|a| Path::new(&a.absolute_path).exists())
+            .into_iter()
+            .collect();
+        let rewriter = self.guid_rewriter(&assets, &[]);
+        Ok(self.prepare(&assets, dest_folder, &rewriter)?.0)
+    }
+
+    /// List what `export_bundle` would write, without writing anything.
+    pub fn plan_bundle(&self, roots: &[Asset], dest_folder: &Path, max_depth: usize) -> AppResult<ExportPlan> {
+        let BundleContents { assets, excluded_roots, .. } = self.collect_bundle(roots, max_depth)?;
+        let rewriter = self.guid_rewriter(&assets, &enclosing_folders(&assets));
+        let mut plan = self.prepare(&assets, dest_folder, &rewriter)?.0;
+        plan.excluded_roots = excluded_roots;
+        Ok(plan)
+    }
 
-        if !source_path.exists() {
+    /// List where `export_unitypackage` would write its archive, and which roots its
+    /// type exclusions would leave out.
+    pub fn plan_unitypackage(&self, roots: &[Asset], dest_folder: &Path, max_depth: usize) -> AppResult<ExportPlan> {
+        let BundleContents { excluded_roots, .. } = self.collect_bundle(roots, max_depth)?;
+        self.plan_archive(roots, dest_folder, excluded_roots)
+    }
+
+    fn plan_archive(&self, roots: &[Asset], dest_folder: &Path, excluded_roots: Vec<String>) -> AppResult<ExportPlan> {
+        let relative_path = unitypackage_name(roots);
+        let mut entry = ExportPlanEntry {
+            status: if dest_folder.join(&relative_path).exists() { "differs" } else { "new" }.to_string(),
+            relative_path,
+            action: String::new(),
+            write_path: None,
+            has_meta: false,
+            reason: None,
+        };
+        let aborted = self.resolve_conflict(&mut entry, dest_folder, &mut HashSet::new());
+        let mut plan = ExportPlan::new(dest_folder, self.policy, vec![entry], aborted);
+        plan.excluded_roots = excluded_roots;
+        Ok(plan)
+    }
+
+    pub fn export_file(&self, asset: &Asset, dest_folder: &Path) -> AppResult<ExportResult> {
+        if !Path::new(&asset.absolute_path).exists() {
             return Ok(ExportResult {
-                success: false,
-                exported_files: vec![],
-                manifest_path: None,
                 error: Some(format!("Source file not found: {}", asset.absolute_path)),
+                ..ExportResult::default()
             });
         }
 
-        let dest_path = dest_folder.join(&asset.relative_path);
+        let assets = vec![asset.clone()];
+        let rewriter = self.guid_rewriter(&assets, &[]);
+        let (plan, files) = self.prepare(&assets, dest_folder, &rewriter)?;
+        Ok(write_planned(plan, files, dest_folder, &rewriter).0)
+    }
+
+    /// Copy `roots` and their dependency trees into `dest_folder` with their `.meta`
+    /// files, next to a `manifest.json` describing the bundle. `manifest.json` itself
+    /// is always replaced. The `.meta` files of enclosing folders are written where
+    /// missing, so regenerated folder GUIDs match the references to them.
+    pub fn export_bundle(
+        &self,
+        roots: &[Asset],
+        dest_folder: &Path,
+        max_depth: usize,
+    ) -> AppResult<ExportResult> {
+        let BundleContents {
+            assets: assets_to_export,
+            packages,
+            excluded_roots,
+        } = self.collect_bundle(roots, max_depth)?;
+        let folders = enclosing_folders(&assets_to_export);
+        let rewriter = self.guid_rewriter(&assets_to_export, &folders);
+        let (mut plan, files) = self.prepare(&assets_to_export, dest_folder, &rewriter)?;
+        plan.excluded_roots = excluded_roots;
+        let (mut result, bundled) = write_planned(plan, files, dest_folder, &rewriter);
+        if result.plan.as_ref().is_some_and(|p| p.aborted) {
+            return Ok(result);
+        }
+        self.write_folder_metas(&folders, dest_folder, &rewriter, &mut result);
+
+        let mut manifest = self.build_manifest(roots, &bundled, max_depth, &rewriter.map)?;
+        manifest.unrewritten_files = result.unrewritten_files.clone();
+        manifest.package_dependencies = packages;
+        let manifest_path = dest_folder.join(MANIFEST_FILE);
+        let manifest_json = serde_json::to_string_pretty(&manifest)?;
+        fs::create_dir_all(dest_folder)?;
+        fs::write(&manifest_path, manifest_json)?;
+
+        result.manifest_path = Some(manifest_path.to_string_lossy().to_string());
+        Ok(result)
+    }
+
+    /// Work out how each asset will be written and how it compares with what is
+    /// already at the destination. Rewritten content is only produced to compare it,
+    /// one file at a time, and produced again when the file is written.
+    fn prepare(
+        &self,
+        assets: &[Asset],
+        dest_folder: &Path,
+        rewriter: &GuidRewriter,
+    ) -> AppResult<(ExportPlan, Vec<PlannedFile>)> {
+        let mut entries = Vec::new();
+        let mut files = Vec::new();
+        let mut claimed = HashSet::new();
+        let mut aborted = false;
+
+        for asset in assets {
+            let source_path = PathBuf::from(&asset.absolute_path);
+            let (payload, unrewritable) = match rewriter.classify(&source_path) {
+                Rewrite::Text => (Payload::Rewrite(source_path), false),
+                Rewrite::Unrewritable => (Payload::Copy(source_path), true),
+                Rewrite::Unchanged => (Payload::Copy(source_path), false),
+            };
 
-        if let Some(parent) = dest_path.parent() {
-            fs::create_dir_all(parent)?;
+            let meta_source = PathBuf::from(format!("{}.meta", asset.absolute_path));
+            let meta = if meta_source.exists() {
+                Some(match rewriter.classify(&meta_source) {
+                    Rewrite::Text => Payload::Rewrite(meta_source),
+                    _ => Payload::Copy(meta_source),
+                })
+            } else {
+                None
+            };
+
+            let dest_path = dest_folder.join(&asset.relative_path);
+            let mut statuses = vec![compare_with_destination(&payload, &dest_path, rewriter)];
+            if let Some(meta) = &meta {
+                statuses.push(compare_with_destination(meta, &meta_path_of(&dest_path), rewriter));
+            }
+            let status = if statuses.contains(&"differs") {
+                "differs"
+            } else if statuses.contains(&"new") {
+                "new"
+            } else {
+                "identical"
+            };
+
+            let mut entry = ExportPlanEntry {
+                relative_path: asset.relative_path.clone(),
+                status: status.to_string(),
+                action: String::new(),
+                write_path: None,
+                has_meta: meta.is_some(),
+                reason: None,
+            };
+            aborted |= self.resolve_conflict(&mut entry, dest_folder, &mut claimed);
+
+            entries.push(entry);
+            files.push(PlannedFile {
+                asset: asset.clone(),
+                payload,
+                meta,
+                unrewritable,
+            });
         }
 
-        fs::copy(source_path, &dest_path)?;
+        Ok((ExportPlan::new(dest_folder, self.policy, entries, aborted), files))
+    }
+
+    /// Fill in the entry's action and write path under the conflict policy. Returns
+    /// whether the conflict aborts the export. A renamed copy keeps its `.meta`, so
+    /// renaming is blocked unless GUIDs are regenerated: the copy would share its GUID
+    /// with the file it sits next to.
+    fn resolve_conflict(&self, entry: &mut ExportPlanEntry, dest_folder: &Path, claimed: &mut HashSet<String>) -> bool {
+        let (action, write_path) = match (entry.status.as_str(), self.policy) {
+            ("new", _) => ("write", Some(entry.relative_path.clone())),
+            ("identical", _) => ("skip", None),
+            (_, ConflictPolicy::Skip) => ("skip", None),
+            (_, ConflictPolicy::Overwrite) => {
+                entry.status = "would_overwrite".to_string();
+                ("overwrite", Some(entry.relative_path.clone()))
+            }
+            (_, ConflictPolicy::Rename) if entry.has_meta && !self.regenerate_guids => {
+                entry.reason = Some(
+                    "A renamed copy would duplicate its GUID. Regenerate GUIDs to export renamed copies".to_string(),
+                );
+                ("blocked", None)
+            }
+            (_, ConflictPolicy::Rename) => ("rename", Some(free_path(dest_folder, &entry.relative_path, claimed))),
+            (_, ConflictPolicy::Abort) => ("skip", None),
+        };
 
-        // Also copy .meta file if it exists
-        let meta_source = PathBuf::from(format!("{}.meta", asset.absolute_path));
-        if meta_source.exists() {
-            let meta_dest = PathBuf::from(format!("{}.meta", dest_path.display()));
-            fs::copy(&meta_source, &meta_dest)?;
+        if let Some(path) = &write_path {
+            claimed.insert(path.clone());
         }
+        entry.action = action.to_string();
+        entry.write_path = write_path;
+        entry.status == "differs" && self.policy == ConflictPolicy::Abort
+    }
+
+    /// Write the bundle as `<dest_folder>/<root name>.unitypackage` (`bundle.unitypackage`
+    /// for several roots): a gzipped tar with a
+    /// `<guid>/` directory per asset holding `asset`, `asset.meta`, `pathname` and, when
+    /// we have a thumbnail, `preview.png`. Enclosing folders that have a `.meta` get an
+    /// entry of their own so their GUIDs survive. `manifest.json` sits at the archive
+    /// root, where Unity's importer ignores it. Assets without a GUID can't be imported
+    /// by Unity and are left out. With regenerated GUIDs the enclosing folders get
+    /// fresh GUIDs as well.
+    pub fn export_unitypackage(
+        &self,
+        roots: &[Asset],
+        dest_folder: &Path,
+        max_depth: usize,
+    ) -> AppResult<ExportResult> {
+        let BundleContents { assets, packages, excluded_roots } = self.collect_bundle(roots, max_depth)?;
+        let assets_to_export: Vec<Asset> = assets
+            .into_iter()
+            .filter(|a| {
+                if a.unity_guid.is_none() {
+                    tracing::warn!("Skipping asset without a GUID: {}", a.relative_path);
+                }
+                a.unity_guid.is_some()
+            })
+            .collect();
+
+        let plan = self.plan_archive(roots, dest_folder, excluded_roots.clone())?;
+        let package_path = match (&plan.entries[0].write_path, plan.aborted) {
+            (Some(path), false) => dest_folder.join(path),
+            _ => {
+                return Ok(ExportResult {
+                    success: !plan.aborted,
+                    skipped_files: std::iter::once(plan.entries[0].relative_path.clone())
+                        .chain(excluded_roots)
+                        .collect(),
+                    error: plan.aborted.then(|| abort_message(&plan)),
+                    plan: Some(plan),
+                    ..ExportResult::default()
+                })
+            }
+        };
+        fs::create_dir_all(dest_folder)?;
+
+        // Written next to the destination and renamed into place once complete, so a
+        // failure never leaves a truncated archive behind
+        let file_name = package_path.file_name().unwrap_or_default().to_string_lossy();
+        let temp_path = package_path.with_file_name(format!(".{}.tmp", file_name));
+        let written = self.write_unitypackage(roots, &assets_to_export, packages, max_depth, &temp_path);
+        let (exported_files, unrewritten_files) = match written.and_then(|w| {
+            fs::rename(&temp_path, &package_path)?;
+            Ok(w)
+        }) {
+            Ok(w) => w,
+            Err(e) => {
+                if let Err(remove_error) = fs::remove_file(&temp_path) {
+                    if remove_error.kind() != io::ErrorKind::NotFound {
+                        tracing::warn!("Failed to remove {}: {}", temp_path.display(), remove_error);
+                    }
+                }
+                return Err(e);
+            }
+        };
 
         Ok(ExportResult {
             success: true,
-            exported_files: vec![asset.relative_path.clone()],
-            manifest_path: None,
-            error: None,
+            exported_files,
+            manifest_path: Some(package_path.to_string_lossy().to_string()),
+            skipped_files: excluded_roots,
+            unrewritten_files,
+            plan: Some(plan),
+            ..ExportResult::default()
         })
     }
 
-    pub fn export_bundle(
+    /// Write the archive for `export_unitypackage` to `path`. Any failure leaves the
+    /// archive unusable, so it ends the write. Returns the exported and unrewritten paths.
+    fn write_unitypackage(
         &self,
-        asset: &Asset,
-        dest_folder: &Path,
+        roots: &[Asset],
+        assets: &[Asset],
+        packages: Vec<PackageDependency>,
         max_depth: usize,
-    ) -> AppResult<ExportResult> {
+        path: &Path,
+    ) -> AppResult<(Vec<String>, Vec<String>)> {
+        let encoder = GzEncoder::new(File::create(path)?, Compression::default());
+        let mut archive = tar::Builder::new(encoder);
+
+        let folders = enclosing_folders(assets);
+        let rewriter = self.guid_rewriter(assets, &folders);
+        let mut written_guids = HashSet::new();
         let mut exported_files = Vec::new();
-        let mut exported_paths = HashSet::new();
-        let mut dependency_edges = Vec::new();
+        let mut unrewritten_files = Vec::new();
 
-        let dep_ids = self.dep_resolver.get_dependency_tree(&asset.id, max_depth)?;
+        for export_asset in assets {
+            let original_guid = export_asset.unity_guid.as_deref().unwrap_or_default();
+            let guid = rewriter.map.get(original_guid).map(String::as_str).unwrap_or(original_guid);
+            if !written_guids.insert(guid.to_string()) {
+                continue;
+            }
 
-        let mut assets_to_export = vec![asset.clone()];
+            let asset_path = Path::new(&export_asset.absolute_path);
+            match rewriter.classify(asset_path) {
+                Rewrite::Text => append_rewritten(&mut archive, &format!("{}/asset", guid), asset_path, &rewriter)?,
+                Rewrite::Unrewritable => {
+                    unrewritten_files.push(export_asset.relative_path.clone());
+                    append_file(&mut archive, &format!("{}/asset", guid), asset_path)?
+                }
+                Rewrite::Unchanged => append_file(&mut archive, &format!("{}/asset", guid), asset_path)?,
+            }
 
-        for dep_id in &dep_ids {
-            if let Some(dep_asset) = self.db.get_asset(dep_id)? {
-                assets_to_export.push(dep_asset);
+            let meta_path = PathBuf::from(format!("{}.meta", export_asset.absolute_path));
+            if meta_path.exists() {
+                match rewriter.classify(&meta_path) {
+                    Rewrite::Text => {
+                        append_rewritten(&mut archive, &format!("{}/asset.meta", guid), &meta_path, &rewriter)?
+                    }
+                    _ => append_file(&mut archive, &format!("{}/asset.meta", guid), &meta_path)?,
+                }
             }
-        }
 
-        for export_asset in &assets_to_export {
-            let source_path = Path::new(&export_asset.absolute_path);
+            append_bytes(
+                &mut archive,
+                &format!("{}/pathname", guid),
+                normalize_folder_path(&export_asset.relative_path).as_bytes(),
+            )?;
 
-            if !source_path.exists() {
-                tracing::warn!("Skipping missing file: {}", export_asset.absolute_path);
-                continue;
+            let preview = export_asset
+                .thumbnail_path
+                .as_deref()
+                .map(Path::new)
+                .filter(|p| p.extension().is_some_and(|e| e.eq_ignore_ascii_case("png")) && p.exists());
+            if let Some(preview) = preview {
+                append_file(&mut archive, &format!("{}/preview.png", guid), preview)?;
             }
 
-            if exported_paths.contains(&export_asset.relative_path) {
+            exported_files.push(export_asset.relative_path.clone());
+        }
+
+        // Folder entries, so the importer recreates them with their GUIDs
+        for folder in &folders {
+            let guid = rewriter.map.get(&folder.guid).unwrap_or(&folder.guid);
+            if !written_guids.insert(guid.clone()) {
                 continue;
             }
+            match rewriter.classify(&folder.meta_path) {
+                Rewrite::Text => {
+                    append_rewritten(&mut archive, &format!("{}/asset.meta", guid), &folder.meta_path, &rewriter)?
+                }
+                _ => append_file(&mut archive, &format!("{}/asset.meta", guid), &folder.meta_path)?,
+            }
+            append_bytes(&mut archive, &format!("{}/pathname", guid), folder.relative_path.as_bytes())?;
+        }
+
+        let mut manifest = self.build_manifest(roots, assets, max_depth, &rewriter.map)?;
+        manifest.unrewritten_files = unrewritten_files.clone();
+        manifest.package_dependencies = packages;
+        append_bytes(&mut archive, MANIFEST_FILE, serde_json::to_string_pretty(&manifest)?.as_bytes())?;
 
-            let dest_path = dest_folder.join(&export_asset.relative_path);
+        archive.into_inner()?.finish()?.sync_all()?;
+
+        Ok((exported_files, unrewritten_files))
+    }
 
-            if let Some(parent) = dest_path.parent() {
-                fs::create_dir_all(parent)?;
+    /// The roots and everything they depend on within `max_depth` over edges of at least
+    /// the exporter's minimum confidence, once each however
+    /// many roots share it. Files missing on disk and excluded types are skipped, and
+    /// dependencies reached only through an excluded asset are left out with it;
+    /// excluded roots are reported.
+    /// Package assets end the walk: they are listed as package dependencies, not copied.
+    fn collect_bundle(&self, roots: &[Asset], max_depth: usize) -> AppResult<BundleContents> {
+        let first = roots
+            .first()
+            .ok_or_else(|| AppError::Custom("Nothing to export".to_string()))?;
+        if roots.iter().any(|r| r.project_id != first.project_id) {
+            return Err(AppError::Custom("Bundle roots must come from one project".to_string()));
+        }
+
+        let graph = self.graphs.get(&self.db, &first.project_id)?;
+        let mut visited: HashSet<String> = HashSet::new();
+        let mut queue: VecDeque<(Asset, usize)> = VecDeque::new();
+        for root in roots {
+            if visited.insert(root.id.clone()) {
+                queue.push_back((root.clone(), 0));
             }
+        }
 
-            fs::copy(source_path, &dest_path)?;
+        let mut candidates = Vec::new();
+        let mut packages = Vec::new();
+        let mut excluded_roots = Vec::new();
+        while let Some((asset, depth)) = queue.pop_front() {
+            if self.excluded_types.contains(&asset.asset_type.to_lowercase()) {
+                if depth == 0 {
+                    excluded_roots.push(asset.relative_path);
+                }
+                continue;
+            }
+            if asset_scope(&asset.relative_path) == SCOPE_PACKAGE {
+                packages.push(PackageDependency {
+                    package: package_of_path(&asset.relative_path).unwrap_or_default(),
+                    relative_path: asset.relative_path,
+                    unity_guid: asset.unity_guid,
+                });
+                continue;
+            }
+            if depth < max_depth {
+                let edges = graph
+                    .dependencies(&asset.id)
+                    .iter()
+                    .filter(|e| meets_confidence(&e.confidence, &self.min_confidence));
+                for edge in edges {
+                    if !visited.insert(edge.to.clone()) {
+                        continue;
+                    }
+                    if let Some(dep_asset) = self.db.get_asset(&edge.to)? {
+                        queue.push_back((dep_asset, depth + 1));
+                    }
+                }
+            }
+            candidates.push(asset);
+        }
 
-            let meta_source = PathBuf::from(format!("{}.meta", export_asset.absolute_path));
-            if meta_source.exists() {
-                let meta_dest = PathBuf::from(format!("{}.meta", dest_path.display()));
-                let _ = fs::copy(&meta_source, &meta_dest);
+        let mut seen_paths = HashSet::new();
+        let mut assets = Vec::new();
+        for candidate in candidates {
+            if !Path::new(&candidate.absolute_path).exists() {
+                tracing::warn!("Skipping missing file: {}", candidate.absolute_path);
+                continue;
+            }
+            if seen_paths.insert(candidate.relative_path.clone()) {
+                assets.push(candidate);
             }
+        }
+        packages.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
 
-            exported_files.push(export_asset.relative_path.clone());
-            exported_paths.insert(export_asset.relative_path.clone());
+        Ok(BundleContents {
+            assets,
+            packages,
+            excluded_roots,
+        })
+    }
+
+    /// Fresh GUIDs for `assets` and their enclosing `folders` when regenerating,
+    /// otherwise a rewriter that changes nothing. The map is complete before anything
+    /// is written, so every file is rewritten against the same GUIDs.
+    fn guid_rewriter(&self, assets: &[Asset], folders: &[BundleFolder]) -> GuidRewriter {
+        let map = if self.regenerate_guids {
+            assets
+                .iter()
+                .filter_map(|a| a.unity_guid.clone())
+                .chain(folders.iter().map(|f| f.guid.clone()))
+                .map(|guid| (guid, new_guid()))
+                .collect()
+        } else {
+            BTreeMap::new()
+        };
+        GuidRewriter::new(map)
+    }
+
+    /// Write the `.meta` of each enclosing folder beside the exported files. One already
+    /// at the destination is only replaced under the `overwrite` policy.
+    fn write_folder_metas(
+        &self,
+        folders: &[BundleFolder],
+        dest_folder: &Path,
+        rewriter: &GuidRewriter,
+        result: &mut ExportResult,
+    ) {
+        for folder in folders {
+            let payload = match rewriter.classify(&folder.meta_path) {
+                Rewrite::Text => Payload::Rewrite(folder.meta_path.clone()),
+                _ => Payload::Copy(folder.meta_path.clone()),
+            };
+            let dest_path = dest_folder.join(format!("{}.meta", folder.relative_path));
+            let write = match compare_with_destination(&payload, &dest_path, rewriter) {
+                "new" => true,
+                "differs" => self.policy == ConflictPolicy::Overwrite,
+                _ => false,
+            };
+            if !write {
+                continue;
+            }
+            if let Err(e) = write_payload(&payload, &dest_path, rewriter) {
+                result.failures.push(ExportFailure {
+                    path: format!("{}.meta", folder.relative_path),
+                    error: e.to_string(),
+                });
+            }
         }
 
-        for export_asset in &assets_to_export {
-            let deps = self.db.get_dependencies(&export_asset.id)?;
+        if !result.failures.is_empty() {
+            result.success = false;
+            result.error = Some(format!("{} files failed to export", result.failures.len()));
+        }
+    }
 
-            for dep in deps {
-                if let Some(to_asset_id) = dep.to_asset_id {
-                    if let Some(to_asset) = self.db.get_asset(&to_asset_id)? {
-                        if exported_paths.contains(&to_asset.relative_path) {
-                            dependency_edges.push(DependencyEdge {
-                                from: export_asset.relative_path.clone(),
-                                to: to_asset.relative_path.clone(),
-                                relation_type: dep.relation_type,
-                            });
-                        }
-                    }
+    fn build_manifest(
+        &self,
+        roots: &[Asset],
+        exported: &[Asset],
+        max_depth: usize,
+        guid_map: &BTreeMap<String, String>,
+    ) -> AppResult<ExportManifest> {
+        let asset = &roots[0];
+        let graph = self.graphs.get(&self.db, &asset.project_id)?;
+        let exported_by_id: HashMap<&str, &str> = exported
+            .iter()
+            .map(|a| (a.id.as_str(), a.relative_path.as_str()))
+            .collect();
+
+        let mut dependency_edges = Vec::new();
+        for (from_id, from_path) in &exported_by_id {
+            for edge in graph.dependencies(from_id) {
+                if let Some(to_path) = exported_by_id.get(edge.to.as_str()) {
+                    dependency_edges.push(DependencyEdge {
+                        from: from_path.to_string(),
+                        to: to_path.to_string(),
+                        relation_type: edge.relation_type.clone(),
+                    });
                 }
             }
         }
@@ -168,34 +793,422 @@ impl Exporter {
                 .unwrap_or(""),
         )?;
 
-        let manifest = ExportManifest {
+        Ok(ExportManifest {
             version: "1.0".to_string(),
             exported_at: chrono::Utc::now().to_rfc3339(),
             source_project: project
                 .map(|p| p.name)
                 .unwrap_or_else(|| "Unknown".to_string()),
             root_asset: asset.relative_path.clone(),
-            assets: assets_to_export
+            roots: roots.iter().map(|r| r.relative_path.clone()).collect(),
+            max_depth: Some(max_depth),
+            excluded_types: {
+                let mut types: Vec<String> = self.excluded_types.iter().cloned().collect();
+                types.sort();
+                types
+            },
+            assets: exported
                 .iter()
-                .filter(|a| exported_paths.contains(&a.relative_path))
                 .map(|a| ExportedAsset {
                     relative_path: a.relative_path.clone(),
                     asset_type: a.asset_type.clone(),
-                    unity_guid: a.unity_guid.clone(),
+                    unity_guid: a
+                        .unity_guid
+                        .as_ref()
+                        .map(|guid| guid_map.get(guid).unwrap_or(guid).clone()),
                 })
                 .collect(),
             dependency_graph: dependency_edges,
-        };
+            guid_map: guid_map.clone(),
+            unrewritten_files: Vec::new(),
+            package_dependencies: Vec::new(),
+        })
+    }
+}
 
-        let manifest_path = dest_folder.join("manifest.json");
-        let manifest_json = serde_json::to_string_pretty(&manifest)?;
-        fs::write(&manifest_path, manifest_json)?;
+/// How to write a file when GUIDs may have been regenerated.
+enum Rewrite {
+    /// Copy the file as is
+    Unchanged,
+    /// Text whose regenerated GUIDs are replaced as it is written
+    Text,
+    /// Binary serialized file referencing regenerated GUIDs; copied as is
+    Unrewritable,
+}
 
-        Ok(ExportResult {
-            success: true,
-            exported_files,
-            manifest_path: Some(manifest_path.to_string_lossy().to_string()),
-            error: None,
+/// Replaces original GUIDs with regenerated ones in `.meta` files and text-serialized
+/// assets. GUIDs are matched as whole 32-digit hex words, which covers YAML
+/// `guid: …` references as well as Shader Graph JSON and `"GUID:…"` assembly references.
+struct GuidRewriter {
+    map: BTreeMap<String, String>,
+    guid_regex: Regex,
+}
+
+impl GuidRewriter {
+    fn new(map: BTreeMap<String, String>) -> Self {
+        Self {
+            map,
+            guid_regex: Regex::new(r"(?-u)\b[0-9a-f]{32}\b").unwrap(),
+        }
+    }
+
+    fn classify(&self, path: &Path) -> Rewrite {
+        if self.map.is_empty() {
+            return Rewrite::Unchanged;
+        }
+
+        let extension = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let is_yaml = YAML_EXTENSIONS.contains(&extension.as_str());
+
+        if is_yaml && !has_yaml_header(path) {
+            if let Some(info) = read_serialized_file_info(path) {
+                if info.externals.iter().any(|e| self.map.contains_key(&e.guid)) {
+                    tracing::warn!("Not rewriting GUID references in binary file: {}", path.display());
+                    return Rewrite::Unrewritable;
+                }
+                return Rewrite::Unchanged;
+            }
+        }
+
+        let is_json = JSON_EXTENSIONS.contains(&extension.as_str())
+            || REWRITTEN_JSON_EXTENSIONS.contains(&extension.as_str());
+        if !is_yaml && extension != "meta" && !is_json {
+            return Rewrite::Unchanged;
+        }
+        Rewrite::Text
+    }
+
+    /// The content of `path` with every regenerated GUID replaced, read a line at a
+    /// time. GUIDs are replaced by GUIDs, so the content keeps the file's length.
+    fn reader(&self, path: &Path) -> io::Result<RewritingReader<'_>> {
+        Ok(RewritingReader {
+            rewriter: self,
+            source: BufReader::new(File::open(path)?),
+            line: Vec::new(),
+            position: 0,
         })
     }
+
+    fn rewrite_line(&self, line: &mut Vec<u8>) {
+        let rewritten = self.guid_regex.replace_all(line, |caps: &regex::bytes::Captures| {
+            std::str::from_utf8(&caps[0])
+                .ok()
+                .and_then(|guid| self.map.get(guid))
+                .map(|guid| guid.as_bytes().to_vec())
+                .unwrap_or_else(|| caps[0].to_vec())
+        });
+        if let Cow::Owned(rewritten) = rewritten {
+            *line = rewritten;
+        }
+    }
+}
+
+/// Reads a file through a `GuidRewriter`, holding one line at a time.
+struct RewritingReader<'a> {
+    rewriter: &'a GuidRewriter,
+    source: BufReader<File>,
+    line: Vec<u8>,
+    position: usize,
+}
+
+impl Read for RewritingReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.position == self.line.len() {
+            self.line.clear();
+            self.position = 0;
+            if self.source.read_until(b'\n', &mut self.line)? == 0 {
+                return Ok(0);
+            }
+            self.rewriter.rewrite_line(&mut self.line);
+        }
+
+        let count = buf.len().min(self.line.len() - self.position);
+        buf[..count].copy_from_slice(&self.line[self.position..self.position + count]);
+        self.position += count;
+        Ok(count)
+    }
+}
+
+/// How to produce one exported file from its source.
+enum Payload {
+    Copy(PathBuf),
+    /// Rewritten when written, so no content is held between planning and writing
+    Rewrite(PathBuf),
+}
+
+/// An asset ready to be written: its content and its `.meta`'s.
+struct PlannedFile {
+    asset: Asset,
+    payload: Payload,
+    meta: Option<Payload>,
+    unrewritable: bool,
+}
+
+/// Write the files the plan says to write, collecting per-file failures. Returns the
+/// result and the assets now in the bundle, under the paths they were written to.
+fn write_planned(
+    plan: ExportPlan,
+    files: Vec<PlannedFile>,
+    dest_folder: &Path,
+    rewriter: &GuidRewriter,
+) -> (ExportResult, Vec<Asset>) {
+    let mut result = ExportResult {
+        skipped_files: plan.excluded_roots.clone(),
+        ..ExportResult::default()
+    };
+    let mut bundled = Vec::new();
+
+    if plan.aborted {
+        result.error = Some(abort_message(&plan));
+        result
+            .skipped_files
+            .extend(plan.entries.iter().map(|e| e.relative_path.clone()));
+        result.plan = Some(plan);
+        return (result, bundled);
+    }
+
+    for (entry, file) in plan.entries.iter().zip(files) {
+        let write_path = match &entry.write_path {
+            Some(path) => path,
+            None if entry.action == "blocked" => {
+                result.failures.push(ExportFailure {
+                    path: entry.relative_path.clone(),
+                    error: entry.reason.clone().unwrap_or_default(),
+                });
+                continue;
+            }
+            None => {
+                result.skipped_files.push(entry.relative_path.clone());
+                // An identical copy is already part of the bundle
+                if entry.status == "identical" {
+                    bundled.push(file.asset);
+                }
+                continue;
+            }
+        };
+
+        let dest_path = dest_folder.join(write_path);
+        if let Err(e) = write_payload(&file.payload, &dest_path, rewriter) {
+            result.failures.push(ExportFailure {
+                path: write_path.clone(),
+                error: e.to_string(),
+            });
+            continue;
+        }
+        if let Some(meta) = &file.meta {
+            let meta_dest = meta_path_of(&dest_path);
+            if let Err(e) = write_payload(meta, &meta_dest, rewriter) {
+                // Without its `.meta` Unity would give the copy a fresh GUID, so take it out again
+                for path in [&meta_dest, &dest_path] {
+                    if let Err(e) = fs::remove_file(path) {
+                        if e.kind() != io::ErrorKind::NotFound {
+                            tracing::warn!("Failed to remove partly exported {}: {}", path.display(), e);
+                        }
+                    }
+                }
+                result.failures.push(ExportFailure {
+                    path: write_path.clone(),
+                    error: format!("Failed to write .meta: {}", e),
+                });
+                continue;
+            }
+        }
+
+        if file.unrewritable {
+            result.unrewritten_files.push(write_path.clone());
+        }
+        result.exported_files.push(write_path.clone());
+        let mut asset = file.asset;
+        asset.relative_path = write_path.clone();
+        bundled.push(asset);
+    }
+
+    result.success = result.failures.is_empty();
+    if !result.success {
+        result.error = Some(format!("{} files failed to export", result.failures.len()));
+    }
+    result.plan = Some(plan);
+    (result, bundled)
+}
+
+fn abort_message(plan: &ExportPlan) -> String {
+    format!(
+        "Export aborted: {} files conflict with existing files in {}",
+        plan.conflict_count, plan.dest_folder
+    )
+}
+
+fn write_payload(payload: &Payload, dest: &Path, rewriter: &GuidRewriter) -> io::Result<()> {
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    match payload {
+        Payload::Copy(source) => fs::copy(source, dest).map(|_| ()),
+        Payload::Rewrite(source) => io::copy(&mut rewriter.reader(source)?, &mut File::create(dest)?).map(|_| ()),
+    }
+}
+
+/// `new` if nothing is at `dest`, `identical` if it holds exactly what `payload`
+/// produces, otherwise `differs`. An unreadable file counts as differing.
+fn compare_with_destination(payload: &Payload, dest: &Path, rewriter: &GuidRewriter) -> &'static str {
+    if !dest.exists() {
+        return "new";
+    }
+
+    let identical = match payload {
+        Payload::Copy(source) => files_equal(source, dest).unwrap_or(false),
+        Payload::Rewrite(source) => rewritten_equal(source, dest, rewriter).unwrap_or(false),
+    };
+    if identical {
+        "identical"
+    } else {
+        "differs"
+    }
+}
+
+fn files_equal(a: &Path, b: &Path) -> io::Result<bool> {
+    if !same_size(a, b)? {
+        return Ok(false);
+    }
+    readers_equal(File::open(a)?, File::open(b)?)
+}
+
+/// Whether `dest` holds `source` as rewritten. Rewriting keeps the length, so
+/// differing sizes settle it without reading.
+fn rewritten_equal(source: &Path, dest: &Path, rewriter: &GuidRewriter) -> io::Result<bool> {
+    if !same_size(source, dest)? {
+        return Ok(false);
+    }
+    readers_equal(rewriter.reader(source)?, File::open(dest)?)
+}
+
+fn same_size(a: &Path, b: &Path) -> io::Result<bool> {
+    Ok(fs::metadata(a)?.len() == fs::metadata(b)?.len())
+}
+
+/// Whether two streams of the same length have the same content.
+fn readers_equal(a: impl Read, b: impl Read) -> io::Result<bool> {
+    let mut reader_a = BufReader::new(a);
+    let mut reader_b = BufReader::new(b);
+    let mut buf_a = vec![0u8; 64 * 1024];
+    let mut buf_b = vec![0u8; 64 * 1024];
+
+    loop {
+        let read = reader_a.read(&mut buf_a)?;
+        if read == 0 {
+            return Ok(true);
+        }
+        reader_b.read_exact(&mut buf_b[..read])?;
+        if buf_a[..read] != buf_b[..read] {
+            return Ok(false);
+        }
+    }
+}
+
+/// The folders enclosing `assets` that have a `.meta` with a GUID, outermost first.
+fn enclosing_folders(assets: &[Asset]) -> Vec<BundleFolder> {
+    let mut seen = HashSet::new();
+    let mut folders = Vec::new();
+
+    for asset in assets {
+        let relative_path = normalize_folder_path(&asset.relative_path);
+        let project_root = match Path::new(&asset.absolute_path)
+            .ancestors()
+            .nth(Path::new(&relative_path).components().count())
+        {
+            Some(root) => root,
+            None => continue,
+        };
+
+        for folder in Path::new(&relative_path).ancestors().skip(1) {
+            let folder = folder.to_string_lossy().to_string();
+            if folder.is_empty() || folder == "Assets" || !seen.insert(folder.clone()) {
+                continue;
+            }
+            let meta_path = project_root.join(format!("{}.meta", folder));
+            if let Some(guid) = read_unity_guid(&meta_path) {
+                folders.push(BundleFolder {
+                    relative_path: folder,
+                    meta_path,
+                    guid,
+                });
+            }
+        }
+    }
+
+    folders.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+    folders
+}
+
+fn meta_path_of(path: &Path) -> PathBuf {
+    PathBuf::from(format!("{}.meta", path.display()))
+}
+
+/// First `name N.ext` beside `relative_path` that is free on disk and not already
+/// taken by the plan.
+fn free_path(dest_folder: &Path, relative_path: &str, claimed: &HashSet<String>) -> String {
+    let path = Path::new(relative_path);
+    let parent = path.parent().unwrap_or(Path::new(""));
+    let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+
+    (1..)
+        .map(|n| parent.join(format!("{} {}{}", stem, n, extension)).to_string_lossy().to_string())
+        .find(|candidate| {
+            let dest = dest_folder.join(candidate);
+            !claimed.contains(candidate) && !dest.exists() && !meta_path_of(&dest).exists()
+        })
+        .unwrap()
+}
+
+fn unitypackage_name(roots: &[Asset]) -> String {
+    let stem = match roots {
+        [root] => Path::new(&root.file_name)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "bundle".to_string()),
+        _ => "bundle".to_string(),
+    };
+    format!("{}.unitypackage", stem)
+}
+
+/// A random GUID in Unity's 32 lowercase hex digit form.
+fn new_guid() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+fn append_file<W: Write>(archive: &mut tar::Builder<W>, name: &str, path: &Path) -> AppResult<()> {
+    let mut file = File::open(path)?;
+    archive.append_file(name, &mut file)?;
+    Ok(())
+}
+
+fn append_bytes<W: Write>(archive: &mut tar::Builder<W>, name: &str, data: &[u8]) -> AppResult<()> {
+    append_data(archive, name, data.len() as u64, data)
+}
+
+/// Stream `path` through `rewriter` into the archive. The entry size is known up front
+/// because rewriting keeps the length.
+fn append_rewritten<W: Write>(
+    archive: &mut tar::Builder<W>,
+    name: &str,
+    path: &Path,
+    rewriter: &GuidRewriter,
+) -> AppResult<()> {
+    append_data(archive, name, fs::metadata(path)?.len(), rewriter.reader(path)?)
+}
+
+fn append_data<W: Write>(archive: &mut tar::Builder<W>, name: &str, size: u64, data: impl Read) -> AppResult<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(size);
+    header.set_mode(0o644);
+    header.set_mtime(chrono::Utc::now().timestamp().max(0) as u64);
+    archive.append_data(&mut header, name, data)?;
+    Ok(())
 }
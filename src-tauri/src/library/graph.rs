@@ -0,0 +1,450 @@
+use crate::library::db::{Database, Dependency};
+use crate::library::deps::meets_confidence;
+use crate::library::error::AppResult;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// A resolved `library_dependencies` edge between two indexed assets.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    pub relation_type: String,
+    pub confidence: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Follow edges from an asset to what it references
+    Dependencies,
+    /// Follow edges from an asset to what references it
+    Dependents,
+}
+
+/// An asset reached by a traversal, with its hop count from the start and the
+/// edge it was first reached through.
+#[derive(Debug, Clone)]
+pub struct ReachedAsset {
+    pub asset_id: String,
+    pub depth: usize,
+    pub via: GraphEdge,
+}
+
+/// Adjacency lists of a project's dependency graph in both directions.
+/// Dangling edges (no `to_asset_id`) are left out.
+#[derive(Debug, Default, Clone)]
+pub struct DependencyGraph {
+    forward: HashMap<String, Vec<GraphEdge>>,
+    reverse: HashMap<String, Vec<GraphEdge>>,
+}
+
+impl DependencyGraph {
+    pub fn load(db: &Database, project_id: &str) -> AppResult<Self> {
+        Ok(Self::from_dependencies(db.get_project_dependencies(project_id)?))
+    }
+
+    pub fn from_dependencies(deps: impl IntoIterator<Item = Dependency>) -> Self {
+        let mut graph = Self::default();
+        for dep in deps {
+            graph.add_dependency(dep);
+        }
+        graph
+    }
+
+    fn add_dependency(&mut self, dep: Dependency) {
+        let to = match dep.to_asset_id {
+            Some(id) => id,
+            None => return,
+        };
+
+        let edge = GraphEdge {
+            from: dep.from_asset_id,
+            to,
+            relation_type: dep.relation_type,
+            confidence: dep.confidence,
+        };
+
+        // One edge per asset pair however many objects reference the target; the
+        // per-fileID rows stay in the database. The most confident one is kept.
+        if let Some(existing) = self
+            .forward
+            .get(&edge.from)
+            .and_then(|outgoing| outgoing.iter().find(|e| e.to == edge.to))
+        {
+            if meets_confidence(&existing.confidence, &edge.confidence) {
+                return;
+            }
+            for e in self.forward.get_mut(&edge.from).into_iter().flatten().chain(
+                self.reverse.get_mut(&edge.to).into_iter().flatten(),
+            ) {
+                if e.from == edge.from && e.to == edge.to {
+                    *e = edge.clone();
+                }
+            }
+            return;
+        }
+
+        self.reverse.entry(edge.to.clone()).or_default().push(edge.clone());
+        self.forward.entry(edge.from.clone()).or_default().push(edge);
+    }
+
+    /// Replace the outgoing edges of `asset_id` after it was re-resolved.
+    pub fn set_outgoing(&mut self, asset_id: &str, deps: Vec<Dependency>) {
+        self.remove_outgoing(asset_id);
+        for dep in deps {
+            self.add_dependency(dep);
+        }
+    }
+
+    /// Drop an asset that was removed from the index along with every edge touching it.
+    pub fn remove_asset(&mut self, asset_id: &str) {
+        self.remove_outgoing(asset_id);
+
+        if let Some(incoming) = self.reverse.remove(asset_id) {
+            for edge in incoming {
+                if let Some(outgoing) = self.forward.get_mut(&edge.from) {
+                    outgoing.retain(|e| e.to != asset_id);
+                    if outgoing.is_empty() {
+                        self.forward.remove(&edge.from);
+                    }
+                }
+            }
+        }
+    }
+
+    fn remove_outgoing(&mut self, asset_id: &str) {
+        if let Some(outgoing) = self.forward.remove(asset_id) {
+            for edge in outgoing {
+                if let Some(incoming) = self.reverse.get_mut(&edge.to) {
+                    incoming.retain(|e| e.from != asset_id);
+                    if incoming.is_empty() {
+                        self.reverse.remove(&edge.to);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn dependencies(&self, asset_id: &str) -> &[GraphEdge] {
+        self.forward.get(asset_id).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    pub fn dependents(&self, asset_id: &str) -> &[GraphEdge] {
+        self.reverse.get(asset_id).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    pub fn edges(&self, asset_id: &str, direction: Direction) -> &[GraphEdge] {
+        match direction {
+            Direction::Dependencies => self.dependencies(asset_id),
+            Direction::Dependents => self.dependents(asset_id),
+        }
+    }
+
+    /// Every asset reachable from any of `starts` over edges accepted by `follow`,
+    /// the starts included.
+    pub fn reachable_from<'a>(
+        &self,
+        starts: impl IntoIterator<Item = &'a String>,
+        direction: Direction,
+        follow: impl Fn(&GraphEdge) -> bool,
+    ) -> HashSet<String> {
+        let mut reachable: HashSet<String> = HashSet::new();
+        let mut queue: VecDeque<String> = starts.into_iter().cloned().collect();
+
+        while let Some(id) = queue.pop_front() {
+            if !reachable.insert(id.clone()) {
+                continue;
+            }
+            for edge in self.edges(&id, direction).iter().filter(|e| follow(e)) {
+                let next = match direction {
+                    Direction::Dependencies => &edge.to,
+                    Direction::Dependents => &edge.from,
+                };
+                if !reachable.contains(next) {
+                    queue.push_back(next.clone());
+                }
+            }
+        }
+
+        reachable
+    }
+
+    /// Breadth-first walk from `start` over edges accepted by `follow`, so each asset
+    /// is reported at its shortest distance. The start asset itself is not included.
+    pub fn walk(
+        &self,
+        start: &str,
+        direction: Direction,
+        max_depth: Option<usize>,
+        follow: impl Fn(&GraphEdge) -> bool,
+    ) -> Vec<ReachedAsset> {
+        let mut visited: HashSet<&str> = HashSet::new();
+        let mut queue: VecDeque<(&str, usize)> = VecDeque::new();
+        let mut reached = Vec::new();
+
+        visited.insert(start);
+        queue.push_back((start, 0));
+
+        while let Some((id, depth)) = queue.pop_front() {
+            if max_depth.map(|max| depth >= max).unwrap_or(false) {
+                continue;
+            }
+
+            for edge in self.edges(id, direction).iter().filter(|e| follow(e)) {
+                let next = match direction {
+                    Direction::Dependencies => edge.to.as_str(),
+                    Direction::Dependents => edge.from.as_str(),
+                };
+                if visited.insert(next) {
+                    reached.push(ReachedAsset {
+                        asset_id: next.to_string(),
+                        depth: depth + 1,
+                        via: edge.clone(),
+                    });
+                    queue.push_back((next, depth + 1));
+                }
+            }
+        }
+
+        reached
+    }
+
+    /// Strongly connected components that contain a cycle: groups of two or more
+    /// assets that all reach each other, or a single asset referencing itself.
+    /// Uses an iterative Tarjan's algorithm so deep graphs can't overflow the stack.
+    pub fn cyclic_components(&self) -> Vec<Vec<String>> {
+        let nodes: Vec<&str> = self.forward.keys().map(String::as_str).collect();
+        let index_of: HashMap<&str, usize> = nodes.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+        let successors: Vec<Vec<usize>> = nodes
+            .iter()
+            .map(|id| {
+                self.dependencies(id)
+                    .iter()
+                    .filter_map(|e| index_of.get(e.to.as_str()).copied())
+                    .collect()
+            })
+            .collect();
+
+        let mut index = vec![usize::MAX; nodes.len()];
+        let mut lowlink = vec![0; nodes.len()];
+        let mut on_stack = vec![false; nodes.len()];
+        let mut stack: Vec<usize> = Vec::new();
+        let mut next_index = 0;
+        let mut components = Vec::new();
+
+        for root in 0..nodes.len() {
+            if index[root] != usize::MAX {
+                continue;
+            }
+
+            // (node, position of the next successor to visit)
+            let mut call_stack: Vec<(usize, usize)> = vec![(root, 0)];
+            index[root] = next_index;
+            lowlink[root] = next_index;
+            next_index += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            while let Some(&(node, pos)) = call_stack.last() {
+                if let Some(&next) = successors[node].get(pos) {
+                    if let Some(frame) = call_stack.last_mut() {
+                        frame.1 += 1;
+                    }
+                    if index[next] == usize::MAX {
+                        index[next] = next_index;
+                        lowlink[next] = next_index;
+                        next_index += 1;
+                        stack.push(next);
+                        on_stack[next] = true;
+                        call_stack.push((next, 0));
+                    } else if on_stack[next] {
+                        lowlink[node] = lowlink[node].min(index[next]);
+                    }
+                    continue;
+                }
+
+                call_stack.pop();
+                if let Some(&(parent, _)) = call_stack.last() {
+                    lowlink[parent] = lowlink[parent].min(lowlink[node]);
+                }
+
+                if lowlink[node] == index[node] {
+                    let mut component = Vec::new();
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        component.push(member);
+                        if member == node {
+                            break;
+                        }
+                    }
+
+                    let is_cycle = component.len() > 1 || successors[node].contains(&node);
+                    if is_cycle {
+                        components.push(component.into_iter().map(|i| nodes[i].to_string()).collect());
+                    }
+                }
+            }
+        }
+
+        components
+    }
+
+    /// Shortest cycle through `start` using only edges between `members`.
+    pub fn cycle_through(&self, start: &str, members: &HashSet<String>) -> Vec<GraphEdge> {
+        let mut came_from: HashMap<&str, &GraphEdge> = HashMap::new();
+        let mut queue: VecDeque<&str> = VecDeque::new();
+        queue.push_back(start);
+
+        while let Some(id) = queue.pop_front() {
+            for edge in self.dependencies(id) {
+                if !members.contains(&edge.to) {
+                    continue;
+                }
+                if edge.to == start {
+                    let mut path = vec![edge.clone()];
+                    let mut current = id;
+                    while current != start {
+                        let step = came_from[current];
+                        path.push(step.clone());
+                        current = step.from.as_str();
+                    }
+                    path.reverse();
+                    return path;
+                }
+                if !came_from.contains_key(edge.to.as_str()) {
+                    came_from.insert(edge.to.as_str(), edge);
+                    queue.push_back(edge.to.as_str());
+                }
+            }
+        }
+
+        Vec::new()
+    }
+
+    /// Up to `limit` shortest chains of edges leading from `from` to `to`. Empty if
+    /// `to` isn't reachable; all returned chains have the same number of hops.
+    pub fn shortest_paths(&self, from: &str, to: &str, limit: usize) -> Vec<Vec<GraphEdge>> {
+        if from == to || limit == 0 {
+            return Vec::new();
+        }
+
+        // Every edge that lies on some shortest path into a node, found layer by layer
+        let mut depth: HashMap<&str, usize> = HashMap::new();
+        let mut predecessors: HashMap<&str, Vec<&GraphEdge>> = HashMap::new();
+        let mut queue: VecDeque<&str> = VecDeque::new();
+        depth.insert(from, 0);
+        queue.push_back(from);
+
+        while let Some(id) = queue.pop_front() {
+            let d = depth[id];
+            if depth.get(to).map(|&target| d >= target).unwrap_or(false) {
+                break;
+            }
+
+            for edge in self.dependencies(id) {
+                match depth.get(edge.to.as_str()) {
+                    None => {
+                        depth.insert(edge.to.as_str(), d + 1);
+                        predecessors.entry(edge.to.as_str()).or_default().push(edge);
+                        queue.push_back(edge.to.as_str());
+                    }
+                    Some(&existing) if existing == d + 1 => {
+                        predecessors.entry(edge.to.as_str()).or_default().push(edge);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if !depth.contains_key(to) {
+            return Vec::new();
+        }
+
+        // Walk predecessor edges back from the target
+        let mut paths = Vec::new();
+        let mut pending: Vec<(&str, Vec<&GraphEdge>)> = vec![(to, Vec::new())];
+        while let Some((id, suffix)) = pending.pop() {
+            if id == from {
+                paths.push(suffix.into_iter().rev().cloned().collect());
+                if paths.len() >= limit {
+                    break;
+                }
+                continue;
+            }
+            for edge in predecessors.get(id).into_iter().flatten() {
+                let mut next = suffix.clone();
+                next.push(edge);
+                pending.push((edge.from.as_str(), next));
+            }
+        }
+
+        paths
+    }
+}
+
+/// Per-project dependency graphs, loaded from `library_dependencies` on first use and
+/// patched as dependencies are re-resolved. Readers get a cheap `Arc` snapshot.
+#[derive(Default)]
+pub struct GraphCache {
+    graphs: RwLock<HashMap<String, Arc<DependencyGraph>>>,
+    /// Bumped under the write lock whenever cached graphs change, so a graph loaded
+    /// while that happened isn't cached over the newer state
+    generation: AtomicU64,
+}
+
+impl GraphCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, db: &Database, project_id: &str) -> AppResult<Arc<DependencyGraph>> {
+        if let Some(graph) = self.graphs.read().get(project_id) {
+            return Ok(Arc::clone(graph));
+        }
+
+        let generation = self.generation.load(Ordering::SeqCst);
+        let graph = Arc::new(DependencyGraph::load(db, project_id)?);
+
+        let mut graphs = self.graphs.write();
+        if self.generation.load(Ordering::SeqCst) != generation {
+            // Edges changed during the load; use it for this traversal only
+            return Ok(graph);
+        }
+        Ok(Arc::clone(graphs.entry(project_id.to_string()).or_insert(graph)))
+    }
+
+    /// Re-read the outgoing edges of `asset_ids` from the database. Assets no longer
+    /// indexed are dropped. Does nothing if the project's graph isn't loaded yet.
+    pub fn refresh_assets(&self, db: &Database, project_id: &str, asset_ids: &HashSet<String>) -> AppResult<()> {
+        if asset_ids.is_empty() {
+            return Ok(());
+        }
+
+        let mut graphs = self.graphs.write();
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        let graph = match graphs.get_mut(project_id) {
+            Some(g) => Arc::make_mut(g),
+            None => return Ok(()),
+        };
+
+        for asset_id in asset_ids {
+            if db.get_asset(asset_id)?.is_some() {
+                graph.set_outgoing(asset_id, db.get_dependencies(asset_id)?);
+            } else {
+                graph.remove_asset(asset_id);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Forget a project's graph so the next traversal reloads it.
+    pub fn invalidate(&self, project_id: &str) {
+        let mut graphs = self.graphs.write();
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        graphs.remove(project_id);
+    }
+}
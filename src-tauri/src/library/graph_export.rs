@@ -0,0 +1,248 @@
+use crate::library::db::{normalize_folder_path, Asset, Database};
+use crate::library::error::{AppError, AppResult};
+use crate::library::graph::{Direction, GraphCache, GraphEdge};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Which part of the project graph to export.
+#[derive(Debug, Clone)]
+pub enum GraphScope {
+    /// An asset and everything it depends on, up to `max_depth` hops
+    Asset { asset_id: String, max_depth: usize },
+    /// Assets under a folder, plus the assets they reference directly
+    Folder(String),
+    Project,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphNode {
+    pub id: String,
+    pub label: String,
+    pub relative_path: String,
+    pub asset_type: String,
+    pub size_bytes: i64,
+    /// False for nodes pulled in only as the target of an edge leaving the scope
+    pub in_scope: bool,
+}
+
+/// The JSON node/edge export format.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphDocument {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GraphExportResult {
+    pub path: String,
+    pub format: String,
+    pub node_count: usize,
+    pub edge_count: usize,
+}
+
+pub struct GraphExporter {
+    db: Arc<Database>,
+    graphs: Arc<GraphCache>,
+}
+
+impl GraphExporter {
+    pub fn new(db: Arc<Database>, graphs: Arc<GraphCache>) -> Self {
+        Self { db, graphs }
+    }
+
+    /// Collect the nodes and resolved edges of `scope` within a project.
+    pub fn collect(&self, project_id: &str, scope: &GraphScope) -> AppResult<GraphDocument> {
+        let assets = self.db.get_all_assets(project_id)?;
+        let graph = self.graphs.get(&self.db, project_id)?;
+        let by_id: HashMap<&str, &Asset> = assets.iter().map(|a| (a.id.as_str(), a)).collect();
+
+        let in_scope: HashSet<String> = match scope {
+            GraphScope::Asset { asset_id, max_depth } => {
+                if !by_id.contains_key(asset_id.as_str()) {
+                    return Err(AppError::AssetNotFound(asset_id.clone()));
+                }
+                let mut ids: HashSet<String> = graph
+                    .walk(asset_id, Direction::Dependencies, Some(*max_depth), |_| true)
+                    .into_iter()
+                    .map(|r| r.asset_id)
+                    .collect();
+                ids.insert(asset_id.clone());
+                ids
+            }
+            GraphScope::Folder(folder) => {
+                let folder = normalize_folder_path(folder);
+                let prefix = format!("{}/", folder);
+                assets
+                    .iter()
+                    .filter(|a| folder.is_empty() || normalize_folder_path(&a.relative_path).starts_with(&prefix))
+                    .map(|a| a.id.clone())
+                    .collect()
+            }
+            GraphScope::Project => assets.iter().map(|a| a.id.clone()).collect(),
+        };
+
+        let mut edges = Vec::new();
+        let mut boundary: HashSet<String> = HashSet::new();
+        for id in &in_scope {
+            for edge in graph.dependencies(id) {
+                if !in_scope.contains(&edge.to) {
+                    // A closure cut off at max_depth keeps only edges inside it
+                    if matches!(scope, GraphScope::Asset { .. }) {
+                        continue;
+                    }
+                    boundary.insert(edge.to.clone());
+                }
+                edges.push(edge.clone());
+            }
+        }
+
+        let mut nodes: Vec<GraphNode> = in_scope
+            .iter()
+            .map(|id| (id, true))
+            .chain(boundary.iter().map(|id| (id, false)))
+            .filter_map(|(id, in_scope)| {
+                by_id.get(id.as_str()).map(|a| GraphNode {
+                    id: a.id.clone(),
+                    label: a.file_name.clone(),
+                    relative_path: a.relative_path.clone(),
+                    asset_type: a.asset_type.clone(),
+                    size_bytes: a.size_bytes,
+                    in_scope,
+                })
+            })
+            .collect();
+        nodes.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+        edges.sort_by(|a, b| a.from.cmp(&b.from).then_with(|| a.to.cmp(&b.to)));
+
+        Ok(GraphDocument { nodes, edges })
+    }
+
+    /// Render `scope` in `format` (`dot`, `graphml` or `json`) and write it to `dest_path`.
+    pub fn export(
+        &self,
+        project_id: &str,
+        scope: &GraphScope,
+        format: &str,
+        dest_path: &Path,
+    ) -> AppResult<GraphExportResult> {
+        let document = self.collect(project_id, scope)?;
+
+        let content = match format.to_lowercase().as_str() {
+            "dot" => to_dot(&document),
+            "graphml" => to_graphml(&document),
+            "json" => serde_json::to_string_pretty(&document)?,
+            other => return Err(AppError::Custom(format!("Unsupported graph format: {}", other))),
+        };
+
+        if let Some(parent) = dest_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(dest_path, content)?;
+
+        Ok(GraphExportResult {
+            path: dest_path.to_string_lossy().to_string(),
+            format: format.to_lowercase(),
+            node_count: document.nodes.len(),
+            edge_count: document.edges.len(),
+        })
+    }
+}
+
+/// Graphviz DOT, with asset attributes on nodes and relation attributes on edges.
+pub fn to_dot(document: &GraphDocument) -> String {
+    let mut out = String::from("digraph dependencies {\n    node [shape=box];\n");
+
+    for node in &document.nodes {
+        out.push_str(&format!(
+            "    \"{}\" [label=\"{}\", type=\"{}\", size={}, path=\"{}\"{}];\n",
+            escape_dot(&node.id),
+            escape_dot(&node.label),
+            escape_dot(&node.asset_type),
+            node.size_bytes,
+            escape_dot(&node.relative_path),
+            if node.in_scope { "" } else { ", style=dashed" },
+        ));
+    }
+
+    for edge in &document.edges {
+        out.push_str(&format!(
+            "    \"{}\" -> \"{}\" [relation_type=\"{}\", confidence=\"{}\"];\n",
+            escape_dot(&edge.from),
+            escape_dot(&edge.to),
+            escape_dot(&edge.relation_type),
+            escape_dot(&edge.confidence),
+        ));
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+pub fn to_graphml(document: &GraphDocument) -> String {
+    let mut out = String::from(concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+        "  <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n",
+        "  <key id=\"type\" for=\"node\" attr.name=\"type\" attr.type=\"string\"/>\n",
+        "  <key id=\"size\" for=\"node\" attr.name=\"size\" attr.type=\"long\"/>\n",
+        "  <key id=\"path\" for=\"node\" attr.name=\"path\" attr.type=\"string\"/>\n",
+        "  <key id=\"in_scope\" for=\"node\" attr.name=\"in_scope\" attr.type=\"boolean\"/>\n",
+        "  <key id=\"relation_type\" for=\"edge\" attr.name=\"relation_type\" attr.type=\"string\"/>\n",
+        "  <key id=\"confidence\" for=\"edge\" attr.name=\"confidence\" attr.type=\"string\"/>\n",
+        "  <graph id=\"dependencies\" edgedefault=\"directed\">\n",
+    ));
+
+    for node in &document.nodes {
+        out.push_str(&format!(
+            concat!(
+                "    <node id=\"{}\">\n",
+                "      <data key=\"label\">{}</data>\n",
+                "      <data key=\"type\">{}</data>\n",
+                "      <data key=\"size\">{}</data>\n",
+                "      <data key=\"path\">{}</data>\n",
+                "      <data key=\"in_scope\">{}</data>\n",
+                "    </node>\n",
+            ),
+            escape_xml(&node.id),
+            escape_xml(&node.label),
+            escape_xml(&node.asset_type),
+            node.size_bytes,
+            escape_xml(&node.relative_path),
+            node.in_scope,
+        ));
+    }
+
+    for edge in &document.edges {
+        out.push_str(&format!(
+            concat!(
+                "    <edge source=\"{}\" target=\"{}\">\n",
+                "      <data key=\"relation_type\">{}</data>\n",
+                "      <data key=\"confidence\">{}</data>\n",
+                "    </edge>\n",
+            ),
+            escape_xml(&edge.from),
+            escape_xml(&edge.to),
+            escape_xml(&edge.relation_type),
+            escape_xml(&edge.confidence),
+        ));
+    }
+
+    out.push_str("  </graph>\n</graphml>\n");
+    out
+}
+
+fn escape_dot(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
@@ -0,0 +1,258 @@
+use crate::library::db::{normalize_folder_path, Asset};
+use crate::library::deps::{path_key, ProjectAssets, CONFIDENCE_LOW, CONFIDENCE_MEDIUM};
+use crate::library::roots::read_addressable_entries;
+use parking_lot::Mutex;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Files scanned with string heuristics rather than parsed as Unity data.
+pub const HEURISTIC_EXTENSIONS: &[&str] = &["cs", "json", "csv", "obj", "mtl"];
+
+/// An asset a file appears to reference by name or path.
+#[derive(Debug, Clone)]
+pub struct HeuristicMatch {
+    pub target: Asset,
+    pub relation_type: &'static str,
+    pub confidence: &'static str,
+}
+
+/// Name-based lookups over a project, built once per resolver run.
+struct ProjectLookup {
+    /// `Resources.Load` path (relative to a `Resources/` folder, no extension) to assets
+    resources: HashMap<String, Vec<Asset>>,
+    /// Addressables address to asset
+    addressables: HashMap<String, Asset>,
+}
+
+pub struct HeuristicResolver {
+    resources_load_regex: Regex,
+    resources_load_all_regex: Regex,
+    addressables_regex: Regex,
+    data_path_regex: Regex,
+    mtllib_regex: Regex,
+    mtl_map_regex: Regex,
+    lookups: Mutex<HashMap<String, Arc<ProjectLookup>>>,
+}
+
+impl Default for HeuristicResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HeuristicResolver {
+    pub fn new() -> Self {
+        Self {
+            resources_load_regex: Regex::new(r#"Resources\.Load(?:Async)?\s*(?:<[^()]*>)?\s*\(\s*@?"([^"]+)""#).unwrap(),
+            resources_load_all_regex: Regex::new(r#"Resources\.LoadAll\s*(?:<[^()]*>)?\s*\(\s*@?"([^"]+)""#).unwrap(),
+            addressables_regex: Regex::new(
+                r#"Addressables\.(?:LoadAssetAsync|LoadAssetsAsync|InstantiateAsync|LoadSceneAsync)\s*(?:<[^()]*>)?\s*\(\s*@?"([^"]+)""#,
+            )
+            .unwrap(),
+            data_path_regex: Regex::new(r#"Assets(?:/|\\/)[^"',;\r\n\t]+?\.[A-Za-z0-9]+\b"#).unwrap(),
+            mtllib_regex: Regex::new(r"(?m)^[ \t]*mtllib[ \t]+(.+?)[ \t]*$").unwrap(),
+            mtl_map_regex: Regex::new(r"(?m)^[ \t]*(?:map_\w+|bump|disp|decal|refl)[ \t]+(.+?)[ \t]*$").unwrap(),
+            lookups: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Find assets referenced from `content` by string: `Resources.Load` and Addressables
+    /// keys in C#, `Assets/...` paths in JSON and CSV, and OBJ/MTL file links.
+    /// `assets` are the project's indexed assets the matches are looked up in; paths
+    /// that match none of them are added to `unresolved_paths`.
+    pub fn find_references(
+        &self,
+        asset: &Asset,
+        content: &str,
+        assets: &ProjectAssets,
+        unresolved_paths: &mut Vec<String>,
+    ) -> Vec<HeuristicMatch> {
+        let mut matches = match asset.extension.to_lowercase().as_str() {
+            "cs" => self.find_script_loads(asset, content, assets),
+            "json" | "csv" => self.find_data_paths(content, assets, unresolved_paths),
+            "obj" => self.find_sibling_files(
+                asset,
+                content,
+                &self.mtllib_regex,
+                "obj_material_library",
+                assets,
+                unresolved_paths,
+            ),
+            "mtl" => self.find_sibling_files(
+                asset,
+                content,
+                &self.mtl_map_regex,
+                "mtl_texture",
+                assets,
+                unresolved_paths,
+            ),
+            _ => Vec::new(),
+        };
+
+        matches.retain(|m| m.target.id != asset.id);
+        // Edges need a GUID; a target that gains one later is picked up like a new file
+        unresolved_paths.extend(
+            matches
+                .iter()
+                .filter(|m| m.target.unity_guid.is_none())
+                .map(|m| m.target.relative_path.clone()),
+        );
+        matches
+    }
+
+    fn find_script_loads(&self, asset: &Asset, content: &str, assets: &ProjectAssets) -> Vec<HeuristicMatch> {
+        let lookup = self.lookup(&asset.project_id, assets);
+        let mut matches = Vec::new();
+
+        for cap in self.resources_load_regex.captures_iter(content) {
+            for target in lookup.resources.get(cap[1].trim_matches('/')).into_iter().flatten() {
+                matches.push(HeuristicMatch {
+                    target: target.clone(),
+                    relation_type: "resources_load",
+                    confidence: CONFIDENCE_MEDIUM,
+                });
+            }
+        }
+
+        // LoadAll takes a folder; everything under it may be loaded
+        for cap in self.resources_load_all_regex.captures_iter(content) {
+            let prefix = format!("{}/", cap[1].trim_matches('/'));
+            for (key, targets) in &lookup.resources {
+                if key.starts_with(&prefix) {
+                    matches.extend(targets.iter().map(|target| HeuristicMatch {
+                        target: target.clone(),
+                        relation_type: "resources_load",
+                        confidence: CONFIDENCE_LOW,
+                    }));
+                }
+            }
+        }
+
+        for cap in self.addressables_regex.captures_iter(content) {
+            if let Some(target) = lookup.addressables.get(&cap[1]) {
+                matches.push(HeuristicMatch {
+                    target: target.clone(),
+                    relation_type: "addressables_load",
+                    confidence: CONFIDENCE_MEDIUM,
+                });
+            }
+        }
+
+        matches
+    }
+
+    fn find_data_paths(
+        &self,
+        content: &str,
+        assets: &ProjectAssets,
+        unresolved_paths: &mut Vec<String>,
+    ) -> Vec<HeuristicMatch> {
+        let mut matches = Vec::new();
+        let mut seen = HashSet::new();
+
+        for m in self.data_path_regex.find_iter(content) {
+            // JSON may escape forward slashes
+            let path = m.as_str().replace("\\/", "/");
+            if !seen.insert(path.clone()) {
+                continue;
+            }
+            match assets.by_path(Path::new(&path)) {
+                Some(target) => matches.push(HeuristicMatch {
+                    target: target.clone(),
+                    relation_type: "data_path_reference",
+                    confidence: CONFIDENCE_LOW,
+                }),
+                None => unresolved_paths.extend(path_key(Path::new(&path))),
+            }
+        }
+
+        matches
+    }
+
+    /// OBJ `mtllib` and MTL texture map lines name files relative to the file itself.
+    fn find_sibling_files(
+        &self,
+        asset: &Asset,
+        content: &str,
+        regex: &Regex,
+        relation_type: &'static str,
+        assets: &ProjectAssets,
+        unresolved_paths: &mut Vec<String>,
+    ) -> Vec<HeuristicMatch> {
+        let folder = Path::new(&asset.relative_path).parent().unwrap_or(Path::new(""));
+        let mut matches = Vec::new();
+
+        for cap in regex.captures_iter(content) {
+            // Map statements may lead with options (`-bm 0.5 normal.png`); the file comes last
+            let value = cap[1].trim();
+            let candidates = [value, value.rsplit(char::is_whitespace).next().unwrap_or(value)];
+
+            let paths: Vec<_> = candidates
+                .iter()
+                .filter(|n| !n.is_empty())
+                .map(|name| folder.join(name.replace('\\', "/")))
+                .collect();
+
+            match paths.iter().find_map(|path| assets.by_path(path)) {
+                Some(target) => matches.push(HeuristicMatch {
+                    target: target.clone(),
+                    relation_type,
+                    confidence: CONFIDENCE_MEDIUM,
+                }),
+                None => unresolved_paths.extend(paths.iter().filter_map(|path| path_key(path))),
+            }
+        }
+
+        matches
+    }
+
+    fn lookup(&self, project_id: &str, assets: &ProjectAssets) -> Arc<ProjectLookup> {
+        if let Some(lookup) = self.lookups.lock().get(project_id) {
+            return Arc::clone(lookup);
+        }
+
+        let mut resources: HashMap<String, Vec<Asset>> = HashMap::new();
+        for asset in assets.iter() {
+            for key in resources_keys(&asset.relative_path) {
+                resources.entry(key).or_default().push(asset.clone());
+            }
+        }
+
+        let addressables = match assets.root_path() {
+            Some(root) => read_addressable_entries(root)
+                .into_iter()
+                .filter(|e| !e.address.is_empty())
+                .filter_map(|e| Some((e.address, assets.by_guid(&e.guid)?.clone())))
+                .collect(),
+            None => HashMap::new(),
+        };
+
+        let lookup = Arc::new(ProjectLookup { resources, addressables });
+        self.lookups.lock().insert(project_id.to_string(), Arc::clone(&lookup));
+        lookup
+    }
+}
+
+/// Every path `Resources.Load` could use for an asset: relative to each enclosing
+/// `Resources/` folder, without the extension.
+fn resources_keys(relative_path: &str) -> Vec<String> {
+    let path = normalize_folder_path(relative_path);
+    let parts: Vec<&str> = path.split('/').collect();
+    let mut keys = Vec::new();
+
+    for (i, part) in parts.iter().enumerate() {
+        if *part != "Resources" || i + 1 >= parts.len() {
+            continue;
+        }
+        let key = parts[i + 1..].join("/");
+        let key = match key.rfind('.') {
+            Some(dot) if dot > key.rfind('/').map(|s| s + 1).unwrap_or(0) => key[..dot].to_string(),
+            _ => key,
+        };
+        keys.push(key);
+    }
+
+    keys
+}
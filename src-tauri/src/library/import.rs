@@ -0,0 +1,370 @@
+use crate::library::db::{normalize_folder_path, Database, Project};
+use crate::library::deps::normalize_relative_path;
+use crate::library::error::{AppError, AppResult};
+use crate::library::export::{ExportManifest, MANIFEST_FILE};
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// One manifest entry and where it would land in the target project.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportItem {
+    /// Path as recorded in the bundle's manifest
+    pub source_path: String,
+    pub dest_relative_path: String,
+    pub asset_type: String,
+    pub unity_guid: Option<String>,
+    /// `new`, `path_collision`, `guid_collision`, `duplicate` or `missing_in_bundle`.
+    /// Only `new` items are imported.
+    pub status: String,
+    /// Existing asset the item collides with
+    pub conflicting_asset: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportPlan {
+    pub source: String,
+    pub source_project: String,
+    pub root_asset: String,
+    pub target_folder: String,
+    pub items: Vec<ImportItem>,
+    pub new_count: usize,
+    pub path_collision_count: usize,
+    pub guid_collision_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportFailure {
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportResult {
+    pub dry_run: bool,
+    pub plan: ImportPlan,
+    pub imported_files: Vec<String>,
+    pub failures: Vec<ImportFailure>,
+    /// Assets indexed by the rescan of the target folder
+    pub indexed_count: usize,
+}
+
+/// Files of an opened bundle by normalized manifest path.
+struct BundleSource {
+    manifest: ExportManifest,
+    files: HashMap<String, BundleFile>,
+    /// `.meta` of each folder entry of an archive by normalized path
+    folders: HashMap<String, PathBuf>,
+    /// Extraction folder of an archive, removed when the bundle is dropped
+    staging: Option<PathBuf>,
+}
+
+struct BundleFile {
+    asset: PathBuf,
+    meta: Option<PathBuf>,
+}
+
+impl Drop for BundleSource {
+    fn drop(&mut self) {
+        if let Some(staging) = &self.staging {
+            if let Err(e) = fs::remove_dir_all(staging) {
+                tracing::warn!("Failed to remove import staging folder {}: {}", staging.display(), e);
+            }
+        }
+    }
+}
+
+pub struct Importer {
+    db: Arc<Database>,
+}
+
+impl Importer {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Plan where each asset of the bundle at `source` lands under `target_folder` and
+    /// what it collides with, then copy the non-colliding assets and their `.meta`
+    /// files into the project. Bundled folders that don't exist yet are created with
+    /// their `.meta`. With `dry_run` only the plan is returned.
+    pub fn import(
+        &self,
+        project: &Project,
+        source: &Path,
+        target_folder: &str,
+        dry_run: bool,
+    ) -> AppResult<ImportResult> {
+        let bundle = open_bundle(source)?;
+        let plan = self.build_plan(project, source, &bundle, target_folder)?;
+
+        let mut imported_files = Vec::new();
+        let mut failures = Vec::new();
+
+        if !dry_run {
+            let root = Path::new(&project.root_path);
+            let target = Path::new(&plan.target_folder);
+
+            // Folders first, so an existing folder is told apart from one the copies create.
+            // Parents sort before their children.
+            let mut folders: Vec<_> = bundle.folders.iter().collect();
+            folders.sort();
+            for (source_path, meta) in folders {
+                let dest = match dest_path(target, source_path) {
+                    Some(dest) if Path::new(&dest) != target => dest,
+                    _ => continue,
+                };
+                if root.join(&dest).exists() {
+                    continue;
+                }
+                if let Err(e) = create_folder(root, meta, &dest) {
+                    failures.push(ImportFailure {
+                        path: dest,
+                        error: e.to_string(),
+                    });
+                }
+            }
+
+            for item in plan.items.iter().filter(|i| i.status == "new") {
+                let file = &bundle.files[&normalize_folder_path(&item.source_path)];
+                match copy_into(root, file, &item.dest_relative_path) {
+                    Ok(()) => imported_files.push(item.dest_relative_path.clone()),
+                    Err(e) => failures.push(ImportFailure {
+                        path: item.dest_relative_path.clone(),
+                        error: e.to_string(),
+                    }),
+                }
+            }
+        }
+
+        Ok(ImportResult {
+            dry_run,
+            plan,
+            imported_files,
+            failures,
+            indexed_count: 0,
+        })
+    }
+
+    fn build_plan(
+        &self,
+        project: &Project,
+        source: &Path,
+        bundle: &BundleSource,
+        target_folder: &str,
+    ) -> AppResult<ImportPlan> {
+        let target = normalize_relative_path(Path::new(&normalize_folder_path(target_folder)))
+            .filter(|p| p.starts_with("Assets"))
+            .ok_or_else(|| AppError::Custom(format!("Import folder must be inside Assets: {}", target_folder)))?;
+
+        let root = Path::new(&project.root_path);
+        let mut dest_seen = HashSet::new();
+        let mut items = Vec::new();
+
+        for exported in &bundle.manifest.assets {
+            let source_path = normalize_folder_path(&exported.relative_path);
+            let dest = dest_path(&target, &source_path);
+
+            let mut item = ImportItem {
+                source_path: exported.relative_path.clone(),
+                dest_relative_path: dest.clone().unwrap_or_default(),
+                asset_type: exported.asset_type.clone(),
+                unity_guid: exported.unity_guid.clone(),
+                status: "new".to_string(),
+                conflicting_asset: None,
+            };
+
+            let dest = match dest {
+                Some(dest) if bundle.files.contains_key(&source_path) => dest,
+                _ => {
+                    item.status = "missing_in_bundle".to_string();
+                    items.push(item);
+                    continue;
+                }
+            };
+
+            if !dest_seen.insert(dest.clone()) {
+                item.status = "duplicate".to_string();
+            } else if root.join(&dest).exists() || self.db.get_asset_by_relative_path(&project.id, &dest)?.is_some() {
+                item.status = "path_collision".to_string();
+                item.conflicting_asset = Some(dest);
+            } else if let Some(existing) = match &exported.unity_guid {
+                Some(guid) => self.db.get_asset_by_guid(&project.id, guid)?,
+                None => None,
+            } {
+                item.status = "guid_collision".to_string();
+                item.conflicting_asset = Some(existing.relative_path);
+            }
+
+            items.push(item);
+        }
+
+        let count = |status: &str| items.iter().filter(|i| i.status == status).count();
+
+        Ok(ImportPlan {
+            source: source.to_string_lossy().to_string(),
+            source_project: bundle.manifest.source_project.clone(),
+            root_asset: bundle.manifest.root_asset.clone(),
+            target_folder: target.to_string_lossy().to_string(),
+            new_count: count("new"),
+            path_collision_count: count("path_collision"),
+            guid_collision_count: count("guid_collision"),
+            items,
+        })
+    }
+}
+
+/// Where a bundle path lands: paths keep their layout below `Assets/`, re-rooted at
+/// the target folder. `None` if the path would escape it.
+fn dest_path(target: &Path, source_path: &str) -> Option<String> {
+    let inner = match source_path {
+        "Assets" => "",
+        _ => source_path.strip_prefix("Assets/").unwrap_or(source_path),
+    };
+    normalize_relative_path(&target.join(inner))
+        .filter(|p| p.starts_with(target))
+        .map(|p| p.to_string_lossy().to_string())
+}
+
+/// Open a bundle written by `Exporter`: a folder holding `manifest.json` next to the
+/// exported files, or a `.unitypackage` archive, which is extracted to a staging
+/// folder first.
+fn open_bundle(source: &Path) -> AppResult<BundleSource> {
+    if source.is_dir() {
+        let manifest = read_manifest(&source.join(MANIFEST_FILE))?;
+        let files: HashMap<String, BundleFile> = manifest
+            .assets
+            .iter()
+            .filter_map(|a| {
+                let path = normalize_relative_path(Path::new(&normalize_folder_path(&a.relative_path)))?;
+                let asset = source.join(path);
+                let meta = PathBuf::from(format!("{}.meta", asset.display()));
+                asset.is_file().then(|| {
+                    (
+                        normalize_folder_path(&a.relative_path),
+                        BundleFile {
+                            asset,
+                            meta: meta.exists().then_some(meta),
+                        },
+                    )
+                })
+            })
+            .collect();
+
+        // Enclosing folders are written with their `.meta` next to them
+        let mut folders = HashMap::new();
+        for path in files.keys() {
+            for folder in Path::new(path).ancestors().skip(1) {
+                let folder = folder.to_string_lossy().to_string();
+                if folder.is_empty() || folder == "Assets" || folders.contains_key(&folder) {
+                    continue;
+                }
+                let meta = source.join(format!("{}.meta", folder));
+                if meta.is_file() {
+                    folders.insert(folder, meta);
+                }
+            }
+        }
+
+        return Ok(BundleSource {
+            manifest,
+            files,
+            folders,
+            staging: None,
+        });
+    }
+
+    if !source.is_file() {
+        return Err(AppError::Custom(format!("Bundle not found: {}", source.display())));
+    }
+
+    let staging = std::env::temp_dir().join(format!("indiecraft-import-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&staging)?;
+    // Owned from here, so the staging folder is cleaned up on every error path below
+    let mut bundle = BundleSource {
+        manifest: ExportManifest::default(),
+        files: HashMap::new(),
+        folders: HashMap::new(),
+        staging: Some(staging.clone()),
+    };
+
+    // `unpack` refuses entries that would escape the staging folder
+    tar::Archive::new(GzDecoder::new(File::open(source)?)).unpack(&staging)?;
+    bundle.manifest = read_manifest(&staging.join(MANIFEST_FILE))?;
+
+    for entry in fs::read_dir(&staging)? {
+        let dir = entry?.path();
+        let pathname = match fs::read_to_string(dir.join("pathname")) {
+            Ok(p) => normalize_folder_path(p.lines().next().unwrap_or("").trim()),
+            Err(_) => continue,
+        };
+        let asset = dir.join("asset");
+        let meta = dir.join("asset.meta");
+        if !asset.is_file() {
+            // Folder entries carry only a `.meta`
+            if meta.is_file() {
+                bundle.folders.insert(pathname, meta);
+            }
+            continue;
+        }
+        bundle.files.insert(
+            pathname,
+            BundleFile {
+                asset,
+                meta: meta.exists().then_some(meta),
+            },
+        );
+    }
+
+    Ok(bundle)
+}
+
+fn read_manifest(path: &Path) -> AppResult<ExportManifest> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| AppError::Custom(format!("Bundle manifest not readable ({}): {}", path.display(), e)))?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+/// Create a folder of the bundle with its `.meta`, so it keeps its GUID. The folder is
+/// removed again if the `.meta` can't be copied.
+fn create_folder(root: &Path, meta: &Path, dest_relative_path: &str) -> AppResult<()> {
+    let dest = root.join(dest_relative_path);
+    fs::create_dir_all(&dest)?;
+
+    if let Err(e) = fs::copy(meta, format!("{}.meta", dest.display())) {
+        if let Err(e) = fs::remove_dir(&dest) {
+            tracing::warn!("Failed to remove partly imported {}: {}", dest.display(), e);
+        }
+        return Err(e.into());
+    }
+
+    Ok(())
+}
+
+/// Copy an asset and its `.meta` into the project. If the `.meta` can't be copied the
+/// asset is removed again, so Unity doesn't give it a fresh GUID on import.
+fn copy_into(root: &Path, file: &BundleFile, dest_relative_path: &str) -> AppResult<()> {
+    let dest = root.join(dest_relative_path);
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::copy(&file.asset, &dest)?;
+    if let Some(meta) = &file.meta {
+        let meta_dest = PathBuf::from(format!("{}.meta", dest.display()));
+        if let Err(e) = fs::copy(meta, &meta_dest) {
+            for path in [&meta_dest, &dest] {
+                if let Err(e) = fs::remove_file(path) {
+                    if e.kind() != std::io::ErrorKind::NotFound {
+                        tracing::warn!("Failed to remove partly imported {}: {}", path.display(), e);
+                    }
+                }
+            }
+            return Err(e.into());
+        }
+    }
+
+    Ok(())
+}
@@ -1,14 +1,28 @@
-use crate::library::db::{Asset, Database};
+use crate::library::db::{insert_history_row, upsert_asset_row, Asset, AssetHistoryEntry, Database};
 use crate::library::error::AppResult;
+use crate::library::packages::{asset_scope, SCOPE_PACKAGE};
+use crate::library::scanner::ExistingAssetMap;
+use std::collections::HashSet;
 use std::sync::Arc;
 
 pub struct Indexer {
     db: Arc<Database>,
+    record_history: bool,
 }
 
 impl Indexer {
     pub fn new(db: Arc<Database>) -> Self {
-        Self { db }
+        Self {
+            db,
+            record_history: false,
+        }
+    }
+
+    /// Record change history while indexing. Left off for the first scan of a project
+    /// so the initial import doesn't produce an `added` row per file.
+    pub fn with_history(mut self, record_history: bool) -> Self {
+        self.record_history = record_history;
+        self
     }
 
     pub fn upsert_batch(&self, assets: &[Asset]) -> AppResult<usize> {
@@ -19,15 +33,167 @@ impl Indexer {
 
         let mut count = 0;
         for asset in assets {
-            if let Err(e) = self.db.upsert_asset(asset) {
-                tracing::warn!("Failed to upsert asset {}: {}", asset.relative_path, e);
-                continue;
+            // Each asset and its history entry are written together or not at all
+            conn.execute("SAVEPOINT upsert_asset", [])?;
+            match self.write_asset(&conn, asset) {
+                Ok(()) => {
+                    conn.execute("RELEASE upsert_asset", [])?;
+                    count += 1;
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to upsert asset {}: {}", asset.relative_path, e);
+                    conn.execute("ROLLBACK TO upsert_asset", [])?;
+                    conn.execute("RELEASE upsert_asset", [])?;
+                }
             }
-            count += 1;
         }
 
         conn.execute("COMMIT", [])?;
 
         Ok(count)
     }
+
+    fn write_asset(&self, conn: &rusqlite::Connection, asset: &Asset) -> AppResult<()> {
+        // The previous row has to be read before the upsert replaces it
+        let entry = if self.record_history {
+            Some(self.change_entry(asset)?)
+        } else {
+            None
+        };
+
+        upsert_asset_row(conn, asset)?;
+        if let Some(entry) = entry {
+            insert_history_row(conn, &entry)?;
+        }
+
+        Ok(())
+    }
+
+    fn change_entry(&self, asset: &Asset) -> AppResult<AssetHistoryEntry> {
+        let previous = self.db.get_asset(&asset.id)?;
+
+        let entry = match previous {
+            Some(old) => AssetHistoryEntry {
+                id: uuid::Uuid::new_v4().to_string(),
+                project_id: asset.project_id.clone(),
+                asset_id: asset.id.clone(),
+                change_type: "modified".to_string(),
+                relative_path: asset.relative_path.clone(),
+                old_path: None,
+                old_size_bytes: Some(old.size_bytes),
+                new_size_bytes: Some(asset.size_bytes),
+                old_modified_time: Some(old.modified_time),
+                new_modified_time: Some(asset.modified_time),
+                old_content_hash: old.content_hash,
+                new_content_hash: asset.content_hash.clone(),
+                detected_at: asset.updated_at,
+            },
+            None => AssetHistoryEntry {
+                id: uuid::Uuid::new_v4().to_string(),
+                project_id: asset.project_id.clone(),
+                asset_id: asset.id.clone(),
+                change_type: "added".to_string(),
+                relative_path: asset.relative_path.clone(),
+                old_path: None,
+                old_size_bytes: None,
+                new_size_bytes: Some(asset.size_bytes),
+                old_modified_time: None,
+                new_modified_time: Some(asset.modified_time),
+                old_content_hash: None,
+                new_content_hash: asset.content_hash.clone(),
+                detected_at: asset.updated_at,
+            },
+        };
+
+        Ok(entry)
+    }
+
+    /// Handle assets that were indexed before but not seen by the latest scan.
+    /// An asset whose GUID now lives at another path is recorded as moved and its
+    /// history and incoming edges are carried over; everything else is recorded as
+    /// deleted. Stale rows are removed in both cases. Returns the number removed.
+    /// Package-scope rows are left alone unless `include_packages` is set, since an
+    /// interrupted package pass says nothing about what was removed, and are removed
+    /// without a history entry when it is.
+    pub fn reconcile_missing(
+        &self,
+        project_id: &str,
+        existing: &ExistingAssetMap,
+        seen_paths: &HashSet<String>,
+        include_packages: bool,
+    ) -> AppResult<usize> {
+        let now = chrono::Utc::now().timestamp();
+        let mut removed = 0;
+
+        for (relative_path, (asset_id, _, _)) in existing {
+            if seen_paths.contains(relative_path) {
+                continue;
+            }
+            let is_package = asset_scope(relative_path) == SCOPE_PACKAGE;
+            if is_package && !include_packages {
+                continue;
+            }
+            // Cached package files stay out of the project's history
+            if is_package {
+                self.db.remove_asset(asset_id)?;
+                removed += 1;
+                continue;
+            }
+
+            let old = match self.db.get_asset(asset_id)? {
+                Some(a) => a,
+                None => continue,
+            };
+
+            let moved_to = match &old.unity_guid {
+                Some(guid) => self.db.find_asset_by_guid_excluding(project_id, guid, &old.id)?,
+                None => None,
+            };
+
+            let entry = match &moved_to {
+                Some(new) => AssetHistoryEntry {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    project_id: project_id.to_string(),
+                    asset_id: new.id.clone(),
+                    change_type: "moved".to_string(),
+                    relative_path: new.relative_path.clone(),
+                    old_path: Some(old.relative_path.clone()),
+                    old_size_bytes: Some(old.size_bytes),
+                    new_size_bytes: Some(new.size_bytes),
+                    old_modified_time: Some(old.modified_time),
+                    new_modified_time: Some(new.modified_time),
+                    old_content_hash: old.content_hash.clone(),
+                    new_content_hash: new.content_hash.clone(),
+                    detected_at: now,
+                },
+                None => AssetHistoryEntry {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    project_id: project_id.to_string(),
+                    asset_id: old.id.clone(),
+                    change_type: "deleted".to_string(),
+                    relative_path: old.relative_path.clone(),
+                    old_path: None,
+                    old_size_bytes: Some(old.size_bytes),
+                    new_size_bytes: None,
+                    old_modified_time: Some(old.modified_time),
+                    new_modified_time: None,
+                    old_content_hash: old.content_hash.clone(),
+                    new_content_hash: None,
+                    detected_at: now,
+                },
+            };
+
+            if let Some(new) = &moved_to {
+                self.db.transfer_asset_identity(&old.id, &new.id)?;
+                // The "added" row written while indexing the new path is superseded by the move
+                self.db.delete_added_history(&new.id)?;
+            }
+
+            self.db.insert_history_entry(&entry)?;
+            self.db.remove_asset(&old.id)?;
+            removed += 1;
+        }
+
+        Ok(removed)
+    }
 }
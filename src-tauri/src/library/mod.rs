@@ -1,12 +1,21 @@
+pub mod analysis;
+pub mod binary;
 pub mod commands;
 pub mod db;
 pub mod deps;
 pub mod error;
 pub mod export;
+pub mod graph;
+pub mod graph_export;
+pub mod heuristics;
+pub mod import;
 pub mod indexer;
+pub mod packages;
 pub mod previews;
+pub mod roots;
 pub mod scanner;
 pub mod settings;
 pub mod state;
+pub mod stream;
 
 pub use state::LibraryState;
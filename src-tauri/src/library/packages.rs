@@ -0,0 +1,148 @@
+use crate::library::db::normalize_folder_path;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+const MANIFEST: &str = "Packages/manifest.json";
+const LOCK_FILE: &str = "Packages/packages-lock.json";
+
+/// Where Unity unpacks registry and git packages. Read-only: edits are lost on the
+/// next resolve.
+pub const PACKAGE_CACHE_DIR: &str = "Library/PackageCache";
+
+/// Assets belonging to the project itself.
+pub const SCOPE_PROJECT: &str = "project";
+/// Read-only assets from `Library/PackageCache`.
+pub const SCOPE_PACKAGE: &str = "package";
+
+/// A Unity Package Manager dependency from `manifest.json`, completed by `packages-lock.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpmPackage {
+    pub name: String,
+    /// Resolved version from the lock file, or the manifest's request if unlocked
+    pub version: String,
+    /// Version, URL or `file:` path as written in `manifest.json`
+    pub requested: Option<String>,
+    /// `registry`, `builtin`, `embedded`, `git`, `local`, ...
+    pub source: Option<String>,
+    /// Listed in `manifest.json`, rather than only pulled in by another package
+    pub direct: bool,
+    pub depth: Option<i64>,
+    pub url: Option<String>,
+    pub dependencies: BTreeMap<String, String>,
+}
+
+/// Scope of an indexed asset, from its project-relative path.
+pub fn asset_scope(relative_path: &str) -> &'static str {
+    let path = normalize_folder_path(relative_path);
+    if path.starts_with(&format!("{}/", PACKAGE_CACHE_DIR)) {
+        SCOPE_PACKAGE
+    } else {
+        SCOPE_PROJECT
+    }
+}
+
+/// Name of the package an asset belongs to: `Library/PackageCache/<name>@<version>/...`
+/// or an embedded `Packages/<name>/...`.
+pub fn package_of_path(relative_path: &str) -> Option<String> {
+    let path = normalize_folder_path(relative_path);
+
+    if let Some(rest) = path.strip_prefix(&format!("{}/", PACKAGE_CACHE_DIR)) {
+        let folder = rest.split('/').next()?;
+        let name = folder.split('@').next().unwrap_or(folder);
+        return (!name.is_empty() && rest.contains('/')).then(|| name.to_string());
+    }
+
+    let rest = path.strip_prefix("Packages/")?;
+    let (name, _) = rest.split_once('/')?;
+    Some(name.to_string())
+}
+
+/// Project-relative folder a package is unpacked to under `Library/PackageCache`.
+/// The part after `@` is the version for registry packages and a commit hash for git
+/// ones, so it is found by listing the cache rather than from the lock file.
+pub fn package_cache_folder(project_root: &Path, name: &str) -> Option<String> {
+    fs::read_dir(project_root.join(PACKAGE_CACHE_DIR))
+        .ok()?
+        .flatten()
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .find(|folder| folder.split('@').next() == Some(name))
+        .map(|folder| format!("{}/{}", PACKAGE_CACHE_DIR, folder))
+}
+
+/// Read `Packages/manifest.json` and `Packages/packages-lock.json`. Packages in the
+/// lock file but not the manifest are indirect dependencies. Missing or malformed
+/// files give an empty list.
+pub fn read_upm_packages(project_root: &Path) -> Vec<UpmPackage> {
+    let manifest = read_json(&project_root.join(MANIFEST));
+    let lock = read_json(&project_root.join(LOCK_FILE));
+
+    let requested: BTreeMap<String, String> = manifest
+        .as_ref()
+        .and_then(|m| m.get("dependencies"))
+        .and_then(Value::as_object)
+        .map(|deps| {
+            deps.iter()
+                .filter_map(|(name, v)| v.as_str().map(|v| (name.clone(), v.to_string())))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut packages: BTreeMap<String, UpmPackage> = BTreeMap::new();
+
+    if let Some(locked) = lock.as_ref().and_then(|l| l.get("dependencies")).and_then(Value::as_object) {
+        for (name, entry) in locked {
+            let text = |key: &str| entry.get(key).and_then(Value::as_str).map(str::to_string);
+            packages.insert(
+                name.clone(),
+                UpmPackage {
+                    name: name.clone(),
+                    version: text("version").unwrap_or_default(),
+                    requested: requested.get(name).cloned(),
+                    source: text("source"),
+                    direct: requested.contains_key(name),
+                    depth: entry.get("depth").and_then(Value::as_i64),
+                    url: text("url"),
+                    dependencies: entry
+                        .get("dependencies")
+                        .and_then(Value::as_object)
+                        .map(|deps| {
+                            deps.iter()
+                                .filter_map(|(n, v)| v.as_str().map(|v| (n.clone(), v.to_string())))
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                },
+            );
+        }
+    }
+
+    // Not yet resolved into the lock file (or no lock file at all)
+    for (name, version) in &requested {
+        packages.entry(name.clone()).or_insert_with(|| UpmPackage {
+            name: name.clone(),
+            version: version.clone(),
+            requested: Some(version.clone()),
+            source: None,
+            direct: true,
+            depth: None,
+            url: None,
+            dependencies: BTreeMap::new(),
+        });
+    }
+
+    packages.into_values().collect()
+}
+
+fn read_json(path: &Path) -> Option<Value> {
+    let content = fs::read_to_string(path).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            tracing::warn!("Failed to parse {}: {}", path.display(), e);
+            None
+        }
+    }
+}
@@ -0,0 +1,367 @@
+use crate::library::db::{normalize_folder_path, AddressableAsset, Asset};
+use crate::library::scanner::read_unity_guid;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+const EDITOR_BUILD_SETTINGS: &str = "ProjectSettings/EditorBuildSettings.asset";
+const ADDRESSABLE_GROUPS_DIR: &str = "Assets/AddressableAssetsData/AssetGroups";
+const ADDRESSABLE_SETTINGS: &str = "Assets/AddressableAssetsData/AddressableAssetSettings.asset";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildScene {
+    pub path: String,
+    pub guid: Option<String>,
+    pub enabled: bool,
+}
+
+/// An Addressables group entry as serialized in a group `.asset` file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressableEntry {
+    pub guid: String,
+    pub address: String,
+    pub group: String,
+    pub labels: Vec<String>,
+}
+
+/// Assets that are pulled into a player build regardless of who references them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EntryPoints {
+    pub asset_ids: HashSet<String>,
+    pub build_scenes: usize,
+    pub resources: usize,
+    pub streaming_assets: usize,
+    pub addressables: usize,
+    /// Runtime scripts, compiled into every build whether or not anything references them
+    pub scripts: usize,
+}
+
+/// Parse the scene list from `ProjectSettings/EditorBuildSettings.asset`.
+/// `ProjectSettings/` is excluded from scans, so this reads the file directly.
+pub fn read_build_scenes(project_root: &Path) -> Vec<BuildScene> {
+    let content = match fs::read_to_string(project_root.join(EDITOR_BUILD_SETTINGS)) {
+        Ok(c) => c,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut scenes = Vec::new();
+    let mut current: Option<BuildScene> = None;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+
+        if let Some(value) = trimmed.strip_prefix("- enabled:") {
+            if let Some(scene) = current.take() {
+                scenes.push(scene);
+            }
+            current = Some(BuildScene {
+                path: String::new(),
+                guid: None,
+                enabled: value.trim() == "1",
+            });
+        } else if let Some(scene) = current.as_mut() {
+            if let Some(value) = trimmed.strip_prefix("path:") {
+                scene.path = value.trim().to_string();
+            } else if let Some(value) = trimmed.strip_prefix("guid:") {
+                let guid = value.trim();
+                if !guid.is_empty() {
+                    scene.guid = Some(guid.to_string());
+                }
+            } else if !line.starts_with("    ") {
+                // Left the m_Scenes list
+                scenes.push(current.take().unwrap());
+            }
+        }
+    }
+
+    if let Some(scene) = current.take() {
+        scenes.push(scene);
+    }
+
+    scenes.retain(|s| !s.path.is_empty() || s.guid.is_some());
+    scenes
+}
+
+/// Collect entries, with their group name and labels, from every Addressables group
+/// under `Assets/AddressableAssetsData/AssetGroups`.
+pub fn read_addressable_entries(project_root: &Path) -> Vec<AddressableEntry> {
+    let groups_dir = project_root.join(ADDRESSABLE_GROUPS_DIR);
+    let read_dir = match fs::read_dir(&groups_dir) {
+        Ok(r) => r,
+        Err(_) => return Vec::new(),
+    };
+
+    // The group's own `m_GUID` is a plain key; entries are list items
+    let guid_re = Regex::new(r"^\s*-\s*m_GUID:\s*([a-f0-9]{32})").unwrap();
+    let address_re = Regex::new(r"^\s*m_Address:\s*(.*)$").unwrap();
+    let group_re = Regex::new(r"^\s*m_GroupName:\s*(.*)$").unwrap();
+    let mut entries = Vec::new();
+
+    for entry in read_dir.flatten() {
+        let path = entry.path();
+        if path.extension().map(|e| e != "asset").unwrap_or(true) {
+            continue;
+        }
+
+        let content = match fs::read_to_string(&path) {
+            Ok(c) => c,
+            Err(_) => continue,
+        };
+
+        let group = content
+            .lines()
+            .find_map(|line| group_re.captures(line).map(|caps| unquote_yaml(&caps[1])))
+            .unwrap_or_else(|| {
+                path.file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_default()
+            });
+
+        let mut pending: Option<AddressableEntry> = None;
+        let mut labels_indent: Option<usize> = None;
+        for line in content.lines() {
+            let trimmed = line.trim();
+            let indent = line.len() - line.trim_start().len();
+
+            // Labels are a list at the same indent as their `m_SerializedLabels:` key
+            if let Some(key_indent) = labels_indent {
+                if indent == key_indent && trimmed.starts_with("- ") && !guid_re.is_match(line) {
+                    if let Some(current) = pending.as_mut() {
+                        current.labels.push(unquote_yaml(&trimmed[2..]));
+                    }
+                    continue;
+                }
+                labels_indent = None;
+            }
+
+            if let Some(caps) = guid_re.captures(line) {
+                if let Some(done) = pending.take() {
+                    entries.push(done);
+                }
+                pending = Some(AddressableEntry {
+                    guid: caps[1].to_string(),
+                    address: String::new(),
+                    group: group.clone(),
+                    labels: Vec::new(),
+                });
+            } else if let Some(caps) = address_re.captures(line) {
+                if let Some(current) = pending.as_mut() {
+                    current.address = unquote_yaml(&caps[1]);
+                }
+            } else if trimmed == "m_SerializedLabels:" {
+                labels_indent = Some(indent);
+            }
+        }
+
+        if let Some(done) = pending.take() {
+            entries.push(done);
+        }
+    }
+
+    entries
+}
+
+/// Every label defined in the Addressables settings' label table, used or not.
+pub fn read_addressable_labels(project_root: &Path) -> Vec<String> {
+    let content = match fs::read_to_string(project_root.join(ADDRESSABLE_SETTINGS)) {
+        Ok(c) => c,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut labels = Vec::new();
+    let mut names_indent: Option<usize> = None;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        let indent = line.len() - line.trim_start().len();
+
+        if let Some(key_indent) = names_indent {
+            if indent >= key_indent && trimmed.starts_with("- ") {
+                labels.push(unquote_yaml(&trimmed[2..]));
+                continue;
+            }
+            break;
+        }
+        if trimmed == "m_LabelNames:" {
+            names_indent = Some(indent);
+        }
+    }
+
+    labels
+}
+
+/// Map Addressables entries to the assets they make addressable. Folder entries have
+/// no asset row: their GUID is matched against the `.meta` of the folders holding
+/// indexed assets, and they cover every asset beneath that folder. An asset listed
+/// directly keeps its own entry over its folder's.
+pub fn resolve_addressable_assets(project_root: &Path, assets: &[Asset]) -> Vec<AddressableAsset> {
+    let by_guid: HashMap<&str, &Asset> = assets
+        .iter()
+        .filter_map(|a| a.unity_guid.as_deref().map(|g| (g, a)))
+        .collect();
+
+    let mut resolved: HashMap<String, AddressableAsset> = HashMap::new();
+    let mut folder_entries = Vec::new();
+
+    for entry in read_addressable_entries(project_root) {
+        match by_guid.get(entry.guid.as_str()) {
+            Some(asset) => {
+                resolved.insert(asset.id.clone(), addressable_asset(asset, &entry, None));
+            }
+            None => folder_entries.push(entry),
+        }
+    }
+
+    if !folder_entries.is_empty() {
+        let folders = folders_by_guid(project_root, assets);
+        for entry in folder_entries {
+            let folder = match folders.get(entry.guid.as_str()) {
+                Some(folder) => folder,
+                None => continue,
+            };
+            let prefix = format!("{}/", folder);
+            for asset in assets {
+                if normalize_folder_path(&asset.relative_path).starts_with(&prefix) && !resolved.contains_key(&asset.id) {
+                    resolved.insert(asset.id.clone(), addressable_asset(asset, &entry, Some(folder)));
+                }
+            }
+        }
+    }
+
+    let mut resolved: Vec<AddressableAsset> = resolved.into_values().collect();
+    resolved.sort_by(|a, b| a.group_name.cmp(&b.group_name).then_with(|| a.relative_path.cmp(&b.relative_path)));
+    resolved
+}
+
+/// Strip the quotes YAML puts around scalars with spaces or special characters.
+fn unquote_yaml(value: &str) -> String {
+    let value = value.trim();
+    if value.len() >= 2 {
+        if let Some(inner) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+            return inner.replace("''", "'");
+        }
+        if let Some(inner) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+            return inner.replace("\\\"", "\"");
+        }
+    }
+    value.to_string()
+}
+
+/// GUIDs of the folders enclosing `assets`, read from their `.meta` files, to the
+/// folder's project-relative path.
+fn folders_by_guid(project_root: &Path, assets: &[Asset]) -> HashMap<String, String> {
+    let mut seen = HashSet::new();
+    let mut folders = HashMap::new();
+
+    for asset in assets {
+        let relative_path = normalize_folder_path(&asset.relative_path);
+        for folder in Path::new(&relative_path).ancestors().skip(1) {
+            let folder = folder.to_string_lossy().to_string();
+            if folder.is_empty() || !seen.insert(folder.clone()) {
+                continue;
+            }
+            if let Some(guid) = read_unity_guid(&project_root.join(format!("{}.meta", folder))) {
+                folders.insert(guid, folder);
+            }
+        }
+    }
+
+    folders
+}
+
+/// `folder` is the folder entry the asset is addressable through, if any.
+fn addressable_asset(asset: &Asset, entry: &AddressableEntry, folder: Option<&str>) -> AddressableAsset {
+    AddressableAsset {
+        asset_id: asset.id.clone(),
+        relative_path: asset.relative_path.clone(),
+        file_name: asset.file_name.clone(),
+        asset_type: asset.asset_type.clone(),
+        size_bytes: asset.size_bytes,
+        entry_guid: entry.guid.clone(),
+        // Assets inside a folder entry are addressed by their path under it
+        address: match folder {
+            Some(folder) => {
+                let relative_path = normalize_folder_path(&asset.relative_path);
+                let inner = relative_path.strip_prefix(folder).unwrap_or(&relative_path);
+                format!("{}{}", entry.address, inner)
+            }
+            None => entry.address.clone(),
+        },
+        group_name: entry.group.clone(),
+        labels: entry.labels.clone(),
+        from_folder: folder.is_some(),
+    }
+}
+
+/// `Resources/` folders anywhere under `Assets/` are always included in builds.
+pub fn is_resources_path(relative_path: &str) -> bool {
+    let path = normalize_folder_path(relative_path);
+    path.starts_with("Assets/") && format!("/{}", path).contains("/Resources/")
+}
+
+pub fn is_streaming_assets_path(relative_path: &str) -> bool {
+    normalize_folder_path(relative_path).starts_with("Assets/StreamingAssets/")
+}
+
+/// Assets under `Editor/` folders never ship in a player build.
+pub fn is_editor_only_path(relative_path: &str) -> bool {
+    let path = normalize_folder_path(relative_path);
+    format!("/{}", path).contains("/Editor/")
+}
+
+/// Resolve build scenes, `Resources/`, `StreamingAssets/`, Addressables entries and
+/// runtime scripts to asset ids within `assets`. Scripts count as roots so the assets
+/// they load by string are live too.
+pub fn collect_entry_points(project_root: &Path, assets: &[Asset]) -> EntryPoints {
+    let mut points = EntryPoints::default();
+
+    let by_guid: HashMap<&str, &Asset> = assets
+        .iter()
+        .filter_map(|a| a.unity_guid.as_deref().map(|g| (g, a)))
+        .collect();
+    let by_path: HashMap<String, &Asset> = assets
+        .iter()
+        .map(|a| (normalize_folder_path(&a.relative_path), a))
+        .collect();
+
+    for scene in read_build_scenes(project_root).iter().filter(|s| s.enabled) {
+        let asset = scene
+            .guid
+            .as_deref()
+            .and_then(|g| by_guid.get(g))
+            .or_else(|| by_path.get(&normalize_folder_path(&scene.path)));
+
+        if let Some(asset) = asset {
+            if points.asset_ids.insert(asset.id.clone()) {
+                points.build_scenes += 1;
+            }
+        }
+    }
+
+    for asset in assets {
+        if is_resources_path(&asset.relative_path) && points.asset_ids.insert(asset.id.clone()) {
+            points.resources += 1;
+        } else if is_streaming_assets_path(&asset.relative_path) && points.asset_ids.insert(asset.id.clone()) {
+            points.streaming_assets += 1;
+        }
+    }
+
+    for addressable in resolve_addressable_assets(project_root, assets) {
+        if points.asset_ids.insert(addressable.asset_id) {
+            points.addressables += 1;
+        }
+    }
+
+    for asset in assets {
+        if asset.asset_type == "script"
+            && normalize_folder_path(&asset.relative_path).starts_with("Assets/")
+            && !is_editor_only_path(&asset.relative_path)
+            && points.asset_ids.insert(asset.id.clone())
+        {
+            points.scripts += 1;
+        }
+    }
+
+    points
+}
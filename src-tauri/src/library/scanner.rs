@@ -1,9 +1,12 @@
 use crate::library::db::Asset;
 use crate::library::error::{AppError, AppResult};
+use crate::library::packages::PACKAGE_CACHE_DIR;
 use jwalk::WalkDir;
 use regex::Regex;
-use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
@@ -63,6 +66,8 @@ pub fn classify_file(path: &Path) -> &'static str {
 
         // Models
         "fbx" | "obj" | "blend" | "dae" | "gltf" | "glb" | "3ds" | "max" | "stl" | "ply" => "model",
+        // Wavefront material libraries are text sidecars of OBJ models, not Unity materials
+        "mtl" => "model",
 
         // Materials
         "mat" => "material",
@@ -71,7 +76,7 @@ pub fn classify_file(path: &Path) -> &'static str {
         "prefab" => "prefab",
 
         // Audio
-        "wav" | "mp3" | "ogg" | "aiff" | "aif" | "flac" | "aac" | "m4a" | "wma" => "audio",
+        "wav" | "mp3" | "ogg" | "aiff" | "aif" | "flac" | "aac" | "m4a" | "wma" | "mixer" => "audio",
 
         // Shaders
         "shader" | "shadergraph" | "shadersubgraph" | "compute" | "cginc" | "hlsl" | "glsl" => "shader",
@@ -79,14 +84,16 @@ pub fn classify_file(path: &Path) -> &'static str {
         // Scenes
         "unity" => "scene",
 
-        // ScriptableObjects and other assets
-        "asset" => "scriptable_object",
+        // ScriptableObjects and other Unity-serialized assets
+        "asset" | "spriteatlas" | "spriteatlasv2" | "terrainlayer" | "lighting" | "preset"
+        | "physicmaterial" | "physicsmaterial2d" | "rendertexture" | "cubemap" | "flare"
+        | "guiskin" | "fontsettings" | "brush" | "giparams" => "scriptable_object",
 
         // Video
         "mp4" | "mov" | "avi" | "mkv" | "webm" => "video",
 
         // Animation
-        "anim" | "controller" | "overrideController" => "animation",
+        "anim" | "controller" | "overridecontroller" | "playable" | "mask" | "signal" => "animation",
 
         // Scripts
         "cs" | "js" | "ts" | "py" => "script",
@@ -98,7 +105,7 @@ pub fn classify_file(path: &Path) -> &'static str {
     }
 }
 
-fn read_unity_guid(meta_path: &Path) -> Option<String> {
+pub fn read_unity_guid(meta_path: &Path) -> Option<String> {
     if !meta_path.exists() {
         return None;
     }
@@ -113,6 +120,53 @@ fn read_unity_guid(meta_path: &Path) -> Option<String> {
         .map(|m| m.as_str().to_string())
 }
 
+/// GUIDs declared by `.meta` files inside Unity packages (`Packages/` and
+/// `Library/PackageCache/`), read from disk. Only needed when the package cache
+/// isn't indexed; otherwise references into packages would look like missing assets.
+pub fn collect_package_guids(project_root: &Path) -> HashSet<String> {
+    let re = Regex::new(r"guid:\s*([a-f0-9]{32})").unwrap();
+    let mut guids = HashSet::new();
+
+    for dir in ["Packages", "Library/PackageCache"] {
+        let root = project_root.join(dir);
+        if !root.is_dir() {
+            continue;
+        }
+
+        for entry in WalkDir::new(&root).follow_links(false).into_iter().flatten() {
+            let path = entry.path();
+            if !entry.file_type().is_file() || path.extension().map(|e| e != "meta").unwrap_or(true) {
+                continue;
+            }
+
+            if let Ok(content) = fs::read_to_string(&path) {
+                if let Some(m) = re.captures(&content).and_then(|caps| caps.get(1)) {
+                    guids.insert(m.as_str().to_string());
+                }
+            }
+        }
+    }
+
+    guids
+}
+
+/// SHA-256 of a file's contents, streamed so large assets don't need to fit in memory
+pub fn hash_file(path: &Path) -> Option<String> {
+    let mut file = fs::File::open(path).ok()?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 64 * 1024];
+
+    loop {
+        let read = file.read(&mut buf).ok()?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+
+    Some(format!("{:x}", hasher.finalize()))
+}
+
 /// Count files that would be scanned (quick pre-count for progress)
 pub fn count_scannable_files(
     root: &Path,
@@ -177,14 +231,22 @@ pub fn count_scannable_files(
     Ok(count)
 }
 
+/// What a scan indexes and how it reports back, shared by the scan entry points.
+pub struct ScanOptions<'a> {
+    pub project_id: &'a str,
+    pub ignore_patterns: &'a [String],
+    /// Assets handed to the callback at a time
+    pub batch_size: usize,
+    pub cancel_flag: Arc<AtomicBool>,
+    /// Previously indexed files; those with the same size and modified time are skipped
+    pub existing_assets: Option<&'a ExistingAssetMap>,
+}
+
 pub fn scan_files_batch(
     root: &Path,
-    project_id: &str,
-    ignore_patterns: &[String],
-    batch_size: usize,
-    cancel_flag: Arc<AtomicBool>,
-    existing_assets: Option<&ExistingAssetMap>,
-    mut callback: impl FnMut(Vec<Asset>, usize, &str) -> bool,
+    options: ScanOptions<'_>,
+    seen_paths: &mut HashSet<String>,
+    callback: impl FnMut(Vec<Asset>, usize, &str) -> bool,
 ) -> AppResult<(usize, ScanStats)> {
     if !Scanner::is_valid_folder(root) {
         return Err(AppError::InvalidProject(
@@ -192,6 +254,71 @@ pub fn scan_files_batch(
         ));
     }
 
+    scan_tree(root, root, options, seen_paths, callback)
+}
+
+/// Scan `Library/PackageCache`, which the default `Library/` ignore pattern keeps out
+/// of the project scan. Assets keep project-relative paths. A project without a
+/// package cache scans nothing.
+pub fn scan_package_cache(
+    root: &Path,
+    options: ScanOptions<'_>,
+    seen_paths: &mut HashSet<String>,
+    callback: impl FnMut(Vec<Asset>, usize, &str) -> bool,
+) -> AppResult<(usize, ScanStats)> {
+    let cache_root = root.join(PACKAGE_CACHE_DIR);
+    if !cache_root.is_dir() {
+        return Ok((0, ScanStats::default()));
+    }
+
+    // Drop the patterns that would exclude the cache itself
+    let patterns: Vec<String> = options
+        .ignore_patterns
+        .iter()
+        .filter(|p| !PACKAGE_CACHE_DIR.starts_with(p.trim_end_matches('/')))
+        .cloned()
+        .collect();
+
+    let options = ScanOptions {
+        ignore_patterns: &patterns,
+        ..options
+    };
+    scan_tree(root, &cache_root, options, seen_paths, callback)
+}
+
+/// Rescan one folder of the project, e.g. after files were copied into it. Assets
+/// outside `subfolder` are left alone.
+pub fn scan_subtree(
+    root: &Path,
+    subfolder: &str,
+    options: ScanOptions<'_>,
+    seen_paths: &mut HashSet<String>,
+    callback: impl FnMut(Vec<Asset>, usize, &str) -> bool,
+) -> AppResult<(usize, ScanStats)> {
+    let walk_root = root.join(subfolder);
+    if !walk_root.is_dir() {
+        return Ok((0, ScanStats::default()));
+    }
+
+    scan_tree(root, &walk_root, options, seen_paths, callback)
+}
+
+/// Walk `walk_root`, a folder inside the project `root`, indexing new and changed files.
+fn scan_tree(
+    root: &Path,
+    walk_root: &Path,
+    options: ScanOptions<'_>,
+    seen_paths: &mut HashSet<String>,
+    mut callback: impl FnMut(Vec<Asset>, usize, &str) -> bool,
+) -> AppResult<(usize, ScanStats)> {
+    let ScanOptions {
+        project_id,
+        ignore_patterns,
+        batch_size,
+        cancel_flag,
+        existing_assets,
+    } = options;
+
     let mut batch = Vec::with_capacity(batch_size);
     let mut total_count = 0;
     let mut stats = ScanStats::default();
@@ -199,7 +326,7 @@ pub fn scan_files_batch(
     let root_path = root.to_path_buf();
     let patterns = ignore_patterns.to_vec();
 
-    for entry in WalkDir::new(root)
+    for entry in WalkDir::new(walk_root)
         .follow_links(false)
         .process_read_dir(move |_depth, _path, _state, children| {
             children.retain(|entry| {
@@ -255,6 +382,7 @@ pub fn scan_files_batch(
             .unwrap_or(0);
 
         stats.total_files += 1;
+        seen_paths.insert(relative_path.clone());
 
         // Check if file is unchanged (same modified_time and size_bytes)
         if let Some(existing) = existing_assets {
@@ -284,10 +412,13 @@ pub fn scan_files_batch(
         let unity_guid = read_unity_guid(&meta_path);
 
         // Reuse existing asset ID if the file existed before (but was modified)
-        let asset_id = existing_assets
+        let existing_id = existing_assets
             .and_then(|m| m.get(&relative_path))
-            .map(|(id, _, _)| id.clone())
-            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+            .map(|(id, _, _)| id.clone());
+        // Hashing reads the whole file, so it's kept for changes to indexed files,
+        // where history compares contents; a first scan doesn't read every file
+        let content_hash = existing_id.as_ref().and_then(|_| hash_file(&path));
+        let asset_id = existing_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
 
         let asset = Asset {
             id: asset_id,
@@ -299,7 +430,7 @@ pub fn scan_files_batch(
             asset_type: asset_type.to_string(),
             size_bytes,
             modified_time,
-            content_hash: None,
+            content_hash,
             unity_guid,
             import_type: None,
             thumbnail_path: None,
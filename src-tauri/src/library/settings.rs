@@ -12,6 +12,23 @@ pub struct LibrarySettings {
     pub ignore_patterns: Vec<String>,
     pub thumbnail_size: u32,
     pub scan_on_focus: bool,
+    /// Project-relative folders searched for shader `#include` targets
+    #[serde(default)]
+    pub shader_include_roots: Vec<String>,
+    /// Seconds spent parsing one file for dependencies before giving up on the rest of it
+    #[serde(default = "default_parse_time_budget_secs")]
+    pub parse_time_budget_secs: u64,
+    /// Also index the read-only package contents in `Library/PackageCache`
+    #[serde(default = "default_index_package_cache")]
+    pub index_package_cache: bool,
+}
+
+fn default_parse_time_budget_secs() -> u64 {
+    30
+}
+
+fn default_index_package_cache() -> bool {
+    true
 }
 
 impl Default for LibrarySettings {
@@ -41,6 +58,9 @@ impl Default for LibrarySettings {
             ],
             thumbnail_size: 128,
             scan_on_focus: true,
+            shader_include_roots: Vec::new(),
+            parse_time_budget_secs: default_parse_time_budget_secs(),
+            index_package_cache: default_index_package_cache(),
         }
     }
 }
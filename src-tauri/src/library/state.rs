@@ -1,5 +1,6 @@
 use crate::library::db::Database;
 use crate::library::error::{AppError, AppResult};
+use crate::library::graph::GraphCache;
 use crate::library::settings::LibrarySettings;
 use parking_lot::RwLock;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -8,6 +9,7 @@ use tauri::{AppHandle, Manager};
 
 pub struct LibraryState {
     pub db: Arc<Database>,
+    pub graphs: Arc<GraphCache>,
     pub settings: Arc<RwLock<LibrarySettings>>,
     pub app_handle: AppHandle,
     pub cancel_flag: Arc<AtomicBool>,
@@ -31,6 +33,7 @@ impl LibraryState {
 
         Ok(Self {
             db: Arc::new(db),
+            graphs: Arc::new(GraphCache::new()),
             settings: Arc::new(RwLock::new(settings)),
             app_handle,
             cancel_flag: Arc::new(AtomicBool::new(false)),
@@ -69,4 +72,12 @@ impl LibraryState {
     pub fn is_scan_running(&self) -> bool {
         self.scan_running.load(Ordering::SeqCst)
     }
+
+    /// Mark a scan as running unless one already is. Returns whether the flag was claimed;
+    /// the caller clears it with `set_scan_running(false)` when done.
+    pub fn try_claim_scan(&self) -> bool {
+        self.scan_running
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+    }
 }
@@ -0,0 +1,117 @@
+use crate::library::error::AppResult;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Lines longer than this (mesh `_typelessdata`, baked lighting blobs) are cut into
+/// windows of this size.
+const MAX_SEGMENT_BYTES: usize = 64 * 1024;
+/// Bytes each window of an overlong line repeats from the previous one, so a
+/// reference straddling the cut is still seen whole.
+const SEGMENT_OVERLAP: usize = 256;
+/// Lines are handed to the caller in chunks of roughly this many bytes.
+const CHUNK_BYTES: usize = 1024 * 1024;
+
+/// How far a streamed read got.
+#[derive(Debug, Clone, Copy)]
+pub struct StreamOutcome {
+    pub bytes_read: u64,
+    pub total_bytes: u64,
+    /// Stopped early because the time budget ran out
+    pub timed_out: bool,
+    /// Some bytes weren't UTF-8 and were read as replacement characters
+    pub invalid_utf8: bool,
+    pub elapsed: Duration,
+}
+
+/// Read a text file in chunks of whole lines without holding more than a couple of
+/// megabytes at once. Overlong lines are split into overlapping windows, so patterns
+/// that never span a line break are all still found (possibly twice). Reading stops
+/// once `time_budget` has elapsed.
+pub fn read_text_chunks(
+    path: &Path,
+    time_budget: Duration,
+    mut on_chunk: impl FnMut(&str) -> AppResult<()>,
+) -> AppResult<StreamOutcome> {
+    let file = File::open(path)?;
+    let total_bytes = file.metadata()?.len();
+    let mut reader = BufReader::with_capacity(64 * 1024, file);
+
+    let started = Instant::now();
+    let mut chunk = String::with_capacity(CHUNK_BYTES + MAX_SEGMENT_BYTES);
+    let mut segment: Vec<u8> = Vec::with_capacity(MAX_SEGMENT_BYTES);
+    let mut bytes_read = 0u64;
+    let mut timed_out = false;
+    let mut invalid_utf8 = false;
+
+    loop {
+        let available = reader.fill_buf()?;
+        let at_end = available.is_empty();
+
+        if !at_end {
+            let room = MAX_SEGMENT_BYTES - segment.len();
+            let window = &available[..available.len().min(room)];
+            let (taken, line_done) = match window.iter().position(|&b| b == b'\n') {
+                Some(pos) => (pos + 1, true),
+                None => (window.len(), false),
+            };
+            segment.extend_from_slice(&window[..taken]);
+            reader.consume(taken);
+            bytes_read += taken as u64;
+
+            if !line_done && segment.len() < MAX_SEGMENT_BYTES {
+                continue;
+            }
+        }
+
+        if !segment.is_empty() {
+            match std::str::from_utf8(&segment) {
+                Ok(text) => chunk.push_str(text),
+                Err(e) => {
+                    // A character cut at the window's end isn't invalid
+                    invalid_utf8 |= e.error_len().is_some();
+                    chunk.push_str(&String::from_utf8_lossy(&segment));
+                }
+            }
+            if !chunk.ends_with('\n') {
+                chunk.push('\n');
+            }
+
+            // Continue an overlong line from just before the cut
+            let overlong = segment.len() >= MAX_SEGMENT_BYTES && segment.last() != Some(&b'\n');
+            if overlong {
+                // Start the overlap on a character boundary
+                let mut cut = segment.len() - SEGMENT_OVERLAP;
+                while cut > 0 && segment[cut] & 0xc0 == 0x80 {
+                    cut -= 1;
+                }
+                segment = segment.split_off(cut);
+            } else {
+                segment.clear();
+            }
+        }
+
+        if chunk.len() >= CHUNK_BYTES || (at_end && !chunk.is_empty()) {
+            on_chunk(&chunk)?;
+            chunk.clear();
+
+            if started.elapsed() > time_budget {
+                timed_out = !at_end;
+                break;
+            }
+        }
+
+        if at_end {
+            break;
+        }
+    }
+
+    Ok(StreamOutcome {
+        bytes_read,
+        total_bytes,
+        timed_out,
+        invalid_utf8,
+        elapsed: started.elapsed(),
+    })
+}
@@ -186,8 +186,10 @@ export const useLibraryBackend = create<LibraryBackendState>((set, get) => ({
     try {
       const response = await invoke<{ assets: BackendAsset[]; total: number }>('library_get_assets', {
         projectId: project.id,
-        searchQuery: searchQuery || null,
-        assetTypes: selectedTypes.length > 0 ? selectedTypes : null,
+        filter: {
+          search_query: searchQuery || null,
+          asset_types: selectedTypes.length > 0 ? selectedTypes : null,
+        },
         page,
         pageSize,
       });